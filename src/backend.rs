@@ -0,0 +1,336 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    hint::spin_loop,
+    sync::atomic::{AtomicU8, AtomicU32, AtomicU64, Ordering},
+    time::Duration,
+};
+
+#[cfg(feature = "std")]
+use core::{cell::Cell, sync::atomic::AtomicUsize};
+
+#[cfg(feature = "std")]
+use crate::fallback::Fallback;
+use crate::{platform::Platform, spin::Spin};
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use crate::platform::{
+    FutexHashResult, configure_private_futex_hash, private_futex_hash, set_timer_slack,
+};
+pub use crate::spin::{set_idle_hook, set_wake_hook};
+
+/// The environment variable that selects the backend, if [`set_backend`]
+/// was not called before the first wait or notify.
+pub const BACKEND_VAR: &str = "WAIT_ON_ADDRESS_BACKEND";
+
+/// Marks that the backend has not been chosen yet.
+const UNRESOLVED: u8 = 0;
+
+/// Marks that a custom backend is being written to [`CUSTOM`].
+const INSTALLING: u8 = u8::MAX;
+
+/// The chosen backend, encoded with [`Backend::encode`].
+static SELECTED: AtomicU8 = AtomicU8::new(UNRESOLVED);
+
+/// The installed custom backend, which is written once before
+/// [`SELECTED`] is set to the encoding of [`Backend::Custom`].
+static CUSTOM: CustomSlot = CustomSlot(UnsafeCell::new(None));
+
+/// The number of threads that have installed a backend with [`set_thread_backend`].
+#[cfg(feature = "std")]
+static THREAD_BACKENDS: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// The backend installed for the current thread, if any.
+    static THREAD_BACKEND: Cell<Option<&'static dyn WaitBackend>> = const { Cell::new(None) };
+}
+
+/// An implementation of the operations behind [`AtomicWait`](crate::AtomicWait).
+#[derive(Copy, Clone)]
+pub enum Backend {
+    /// The native wait and wake operations of the target platform.
+    /// On platforms without native support, this is the same as [`Backend::Fallback`].
+    Platform,
+    /// The portable table of OS synchronization primitives.
+    /// Without the `std` feature, this is the same as [`Backend::Spin`].
+    Fallback,
+    /// Bounded spinning, followed by a call to the hook registered with
+    /// [`set_idle_hook`]. Notifies call the hook registered with [`set_wake_hook`].
+    /// This works on any platform, even without `std`.
+    Spin,
+    /// A user-supplied implementation.
+    Custom(&'static dyn WaitBackend),
+}
+
+impl Backend {
+    /// Converts the backend into its representation in [`SELECTED`].
+    fn encode(self) -> u8 {
+        match self {
+            Self::Platform => 1,
+            Self::Fallback => 2,
+            Self::Spin => 3,
+            Self::Custom(_) => 4,
+        }
+    }
+
+    /// Converts a resolved value of [`SELECTED`] back into a backend.
+    fn decode(value: u8) -> Self {
+        match value {
+            1 => Self::Platform,
+            2 => Self::Fallback,
+            3 => Self::Spin,
+            // Safety: the slot is written before the custom backend is
+            // published, and never changes afterward.
+            _ => Self::Custom(unsafe { (*CUSTOM.0.get()).expect("Custom backend was not set") }),
+        }
+    }
+}
+
+impl PartialEq for Backend {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => core::ptr::addr_eq(*a, *b),
+            _ => self.encode() == other.encode(),
+        }
+    }
+}
+
+impl Eq for Backend {}
+
+impl fmt::Debug for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Platform => f.write_str("Platform"),
+            Self::Fallback => f.write_str("Fallback"),
+            Self::Spin => f.write_str("Spin"),
+            Self::Custom(x) => f.debug_tuple("Custom").field(&(*x as *const _)).finish(),
+        }
+    }
+}
+
+/// Chooses the backend for all subsequent atomic waits and notifies.
+///
+/// The backend is fixed the first time that it is needed, so this must be
+/// called before any wait or notify takes place. Otherwise, the backend is
+/// read from the `WAIT_ON_ADDRESS_BACKEND` environment variable when `std` is
+/// enabled, which may be `platform`, `fallback`, or `spin`, and defaults to [`Backend::Platform`].
+/// When the `force-fallback` feature is enabled, the backend is always
+/// [`Backend::Fallback`].
+///
+/// A [`Backend::Custom`] implementation may be installed for platforms or
+/// environments that the crate does not support, like unikernels or
+/// deterministic simulations.
+///
+/// Returns the backend actually in use if it differs from `backend`.
+pub fn set_backend(backend: Backend) -> Result<(), Backend> {
+    if cfg!(feature = "force-fallback") && backend != Backend::Fallback {
+        return Err(Backend::Fallback);
+    }
+
+    let encoded = match backend {
+        Backend::Custom(_) => INSTALLING,
+        _ => backend.encode(),
+    };
+
+    match SELECTED.compare_exchange(UNRESOLVED, encoded, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => {
+            if let Backend::Custom(custom) = backend {
+                // Safety: only the thread that claimed the slot may write it,
+                // and readers wait until it is published.
+                unsafe { *CUSTOM.0.get() = Some(custom) };
+                SELECTED.store(backend.encode(), Ordering::Release);
+            }
+
+            Ok(())
+        }
+        Err(_) => {
+            let current = self::backend();
+            if current == backend {
+                Ok(())
+            } else {
+                Err(current)
+            }
+        }
+    }
+}
+
+/// Gets the backend used for atomic waits and notifies,
+/// choosing it if this has not happened yet.
+pub fn backend() -> Backend {
+    loop {
+        match SELECTED.load(Ordering::Acquire) {
+            UNRESOLVED => return resolve(),
+            INSTALLING => spin_loop(),
+            x => return Backend::decode(x),
+        }
+    }
+}
+
+/// Routes the atomic waits and notifies of the current thread to `backend`,
+/// or back to the global [`backend`] if `None` is given.
+/// Returns the backend that was previously installed for this thread.
+///
+/// This allows user-space schedulers, like stackful coroutine runtimes, to
+/// suspend the running coroutine instead of blocking the whole OS thread.
+/// The hook only affects operations made on this thread: a notify made on
+/// another thread is handled by that thread's backend. Runtimes should
+/// therefore install the same backend on every thread that they manage, and
+/// the backend should forward notifies to the global backend as well if
+/// threads outside of the runtime might wait on the same atomics.
+#[cfg(feature = "std")]
+pub fn set_thread_backend(
+    backend: Option<&'static dyn WaitBackend>,
+) -> Option<&'static dyn WaitBackend> {
+    let previous = THREAD_BACKEND.replace(backend);
+    match (previous.is_some(), backend.is_some()) {
+        (false, true) => {
+            THREAD_BACKENDS.fetch_add(1, Ordering::Relaxed);
+        }
+        (true, false) => {
+            THREAD_BACKENDS.fetch_sub(1, Ordering::Relaxed);
+        }
+        _ => {}
+    }
+
+    previous
+}
+
+/// Gets the backend that was installed for the current thread with `set_thread_backend`, if any.
+pub(crate) fn thread_backend() -> Option<&'static dyn WaitBackend> {
+    #[cfg(feature = "std")]
+    if THREAD_BACKENDS.load(Ordering::Relaxed) != 0
+        && let Ok(x) = THREAD_BACKEND.try_with(Cell::get)
+    {
+        return x;
+    }
+
+    None
+}
+
+/// Gets whether the operations of the current thread use [`Backend::Platform`],
+/// so that platform-specific operations may be used in place of the backend's.
+pub(crate) fn uses_platform() -> bool {
+    thread_backend().is_none() && backend() == Backend::Platform
+}
+
+/// Gets the implementation of the backend for the current thread.
+pub(crate) fn current() -> &'static dyn WaitBackend {
+    if let Some(x) = thread_backend() {
+        return x;
+    }
+
+    implementation(backend())
+}
+
+/// Gets whether the notifies of the current thread's backend are async-signal-safe.
+///
+/// Returns `false` if the global backend has not been chosen yet,
+/// since reading the environment is not async-signal-safe.
+pub(crate) fn is_signal_safe() -> bool {
+    if let Some(x) = thread_backend() {
+        return x.notify_is_signal_safe();
+    }
+
+    match SELECTED.load(Ordering::Acquire) {
+        UNRESOLVED | INSTALLING => false,
+        x => implementation(Backend::decode(x)).notify_is_signal_safe(),
+    }
+}
+
+/// Gets the implementation of `backend`.
+fn implementation(backend: Backend) -> &'static dyn WaitBackend {
+    match backend {
+        Backend::Platform => &Platform,
+        #[cfg(feature = "std")]
+        Backend::Fallback => &Fallback,
+        #[cfg(not(feature = "std"))]
+        Backend::Fallback => &Spin,
+        Backend::Spin => &Spin,
+        Backend::Custom(x) => x,
+    }
+}
+
+/// Fixes the backend according to the crate features and environment.
+#[cold]
+fn resolve() -> Backend {
+    #[cfg(feature = "std")]
+    let backend = if cfg!(feature = "force-fallback") {
+        Backend::Fallback
+    } else {
+        match std::env::var_os(BACKEND_VAR) {
+            Some(x) if x == "fallback" => Backend::Fallback,
+            Some(x) if x == "spin" => Backend::Spin,
+            _ => Backend::Platform,
+        }
+    };
+
+    #[cfg(not(feature = "std"))]
+    let backend = if cfg!(feature = "force-fallback") {
+        Backend::Fallback
+    } else {
+        Backend::Platform
+    };
+
+    match SELECTED.compare_exchange(
+        UNRESOLVED,
+        backend.encode(),
+        Ordering::AcqRel,
+        Ordering::Acquire,
+    ) {
+        Ok(_) => backend,
+        Err(_) => self::backend(),
+    }
+}
+
+/// Implements wait and wake operations for 32- and 64-bit atomics.
+///
+/// A backend may be installed with [`set_backend`], after which every
+/// [`AtomicWait`](crate::AtomicWait) operation is forwarded to it.
+/// Operations on `AtomicI32` and `AtomicI64` are forwarded to the methods
+/// for unsigned atomics of the same size.
+///
+/// # Safety
+///
+/// Waits must be atomic with respect to notifies: if a thread observes that an
+/// atomic holds `value` and goes to sleep, a notify on that atomic made after
+/// the observation must not be lost. Synchronization primitives built on
+/// [`AtomicWait`](crate::AtomicWait) rely on this to avoid sleeping forever.
+/// Waits may return spuriously, and notifies may wake more threads than requested.
+pub unsafe trait WaitBackend: Sync {
+    /// If the value is `value`, wait until woken up or until `timeout` elapses.
+    ///
+    /// This function might also return spuriously,
+    /// without a corresponding wake operation.
+    fn wait32(&self, atomic: &AtomicU32, value: u32, timeout: Option<Duration>);
+
+    /// Wake all threads that are waiting on `atomic`.
+    fn notify_all32(&self, atomic: &AtomicU32);
+
+    /// Wake one thread that is waiting on `atomic`.
+    fn notify_one32(&self, atomic: &AtomicU32);
+
+    /// If the value is `value`, wait until woken up or until `timeout` elapses.
+    ///
+    /// This function might also return spuriously,
+    /// without a corresponding wake operation.
+    fn wait64(&self, atomic: &AtomicU64, value: u64, timeout: Option<Duration>);
+
+    /// Wake all threads that are waiting on `atomic`.
+    fn notify_all64(&self, atomic: &AtomicU64);
+
+    /// Wake one thread that is waiting on `atomic`.
+    fn notify_one64(&self, atomic: &AtomicU64);
+
+    /// Whether the notify methods are async-signal-safe, meaning that they only use
+    /// lock-free operations and raw system calls, so that they may be called from
+    /// signal handlers. Returns `false` unless overridden.
+    fn notify_is_signal_safe(&self) -> bool {
+        false
+    }
+}
+
+/// Holds the installed custom backend.
+struct CustomSlot(UnsafeCell<Option<&'static dyn WaitBackend>>);
+
+unsafe impl Sync for CustomSlot {}
@@ -1,19 +1,19 @@
 use std::{
+    cell::UnsafeCell,
     hint::spin_loop,
-    sync::{Condvar, Mutex, MutexGuard},
+    sync::{Condvar, Mutex, MutexGuard, OnceLock},
     time::Duration,
 };
 
-/// The number of OS synchronization primitives to use.
-const TABLE_SIZE: usize = 256;
+use crate::fallback::{freeze_table_size, index_for_ptr};
 
-/// The table of OS synchronization primitives.
-static TABLE: [TableEntry; TABLE_SIZE] = [TableEntry::DEFAULT; TABLE_SIZE];
+/// The table of OS synchronization primitives, which is allocated on first use.
+static TABLE: OnceLock<Table> = OnceLock::new();
 
 /// Puts the current thread to sleep if `condition` evaluates to `true`.
 /// The thread will be woken after `timeout` if it is provided.
 pub fn wait(ptr: *const (), condition: impl FnOnce() -> bool, timeout: Option<Duration>) {
-    let entry = &TABLE[entry_for_ptr(ptr) as usize];
+    let entry = entry_for_ptr(ptr);
     let mut guard = spin_lock(&entry.mutex);
     if condition() {
         if guard.waiting_count == 0 {
@@ -41,7 +41,7 @@ pub fn wait(ptr: *const (), condition: impl FnOnce() -> bool, timeout: Option<Du
 /// Wakes all threads waiting on `ptr`.
 pub fn notify_all(ptr: *const ()) {
     if !ptr.is_null() {
-        let entry = &TABLE[entry_for_ptr(ptr) as usize];
+        let entry = entry_for_ptr(ptr);
         let metadata = *spin_lock(&entry.mutex);
         if 0 < metadata.waiting_count {
             entry.condvar.notify_all();
@@ -52,7 +52,7 @@ pub fn notify_all(ptr: *const ()) {
 /// Wakes at least one thread waiting on `ptr`.
 pub fn notify_one(ptr: *const ()) {
     if !ptr.is_null() {
-        let entry = &TABLE[entry_for_ptr(ptr) as usize];
+        let entry = entry_for_ptr(ptr);
         let metadata = *spin_lock(&entry.mutex);
         if 0 < metadata.waiting_count {
             if metadata.address.is_null() {
@@ -76,12 +76,71 @@ fn spin_lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
     }
 }
 
-/// Gets the entry index to use for the given address.
-fn entry_for_ptr(ptr: *const ()) -> u8 {
-    let x_64 = ptr as u64;
-    let x_32 = (x_64 >> 32) as u32 ^ x_64 as u32;
-    let x_16 = (x_32 >> 16) as u16 ^ x_32 as u16;
-    (x_16 >> 8) as u8 ^ x_16 as u8
+/// Gets the table entry to use for the given address.
+fn entry_for_ptr(ptr: *const ()) -> &'static TableEntry {
+    #[cfg(unix)]
+    register_fork_handler();
+
+    let table = TABLE.get_or_init(new_table);
+
+    // Safety: the table is only ever mutated by `reinitialize_in_child`,
+    // which runs while the child process has a single thread.
+    let table = unsafe { &*table.0.get() };
+    &table[index_for_ptr(ptr, table.len())]
+}
+
+/// Allocates a table with the configured number of entries.
+fn new_table() -> Table {
+    Table(UnsafeCell::new(
+        (0..freeze_table_size())
+            .map(|_| TableEntry::DEFAULT)
+            .collect(),
+    ))
+}
+
+/// Arranges for [`reinitialize_in_child`] to run after every `fork`.
+#[cfg(unix)]
+fn register_fork_handler() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+    if !REGISTERED.load(Ordering::Acquire) && !REGISTERED.swap(true, Ordering::AcqRel) {
+        unsafe {
+            libc::pthread_atfork(
+                Some(initialize_before_fork),
+                None,
+                Some(reinitialize_in_child),
+            );
+        }
+    }
+}
+
+/// Resets every table entry in a freshly-forked child process.
+///
+/// Other threads of the parent do not exist in the child, so any
+/// mutex they held or wait they were counted in would otherwise
+/// remain forever. The old entries are leaked rather than dropped,
+/// since their state is no longer meaningful.
+#[cfg(unix)]
+extern "C" fn reinitialize_in_child() {
+    if let Some(table) = TABLE.get() {
+        // Entries are overwritten through a raw pointer, since shared
+        // references to them were handed out before the fork.
+        unsafe {
+            let entrys = &raw mut **table.0.get();
+            for i in 0..entrys.len() {
+                std::ptr::write(entrys.cast::<TableEntry>().add(i), TableEntry::DEFAULT);
+            }
+        }
+    }
+}
+
+/// Finishes allocating the table before a `fork`, so that the child
+/// never observes it midway through initialization by another thread.
+#[cfg(unix)]
+extern "C" fn initialize_before_fork() {
+    TABLE.get_or_init(new_table);
 }
 
 /// Holds metadata that gets written while locking.
@@ -122,3 +181,9 @@ impl TableEntry {
         mutex: Mutex::new(WaitMetadata::DEFAULT),
     };
 }
+
+/// The set of all table entries.
+struct Table(UnsafeCell<Box<[TableEntry]>>);
+
+unsafe impl Send for Table {}
+unsafe impl Sync for Table {}
@@ -0,0 +1,150 @@
+use core::sync::atomic::{AtomicI32, AtomicU32, AtomicUsize, Ordering, fence};
+use std::{
+    io,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+};
+
+use crate::AtomicWait;
+
+/// The maximum number of notifiers that may be registered at once.
+const SLOT_COUNT: usize = 64;
+
+/// A registration of an eventfd for an address.
+#[derive(Debug)]
+struct Slot {
+    /// The registered address, or zero if the slot is not in use.
+    address: AtomicUsize,
+    /// The eventfd to signal, or `-1` if the slot is free.
+    fd: AtomicI32,
+    /// The number of notifies that are signaling the eventfd, which
+    /// must not be closed until they are finished.
+    busy: AtomicU32,
+}
+
+/// The registered notifiers. A fixed-size array is used so that notifies
+/// never allocate or take locks, and remain async-signal-safe.
+static SLOTS: [Slot; SLOT_COUNT] = [const {
+    Slot {
+        address: AtomicUsize::new(0),
+        fd: AtomicI32::new(-1),
+        busy: AtomicU32::new(0),
+    }
+}; SLOT_COUNT];
+
+/// The number of slots that are in use, so that notifies
+/// can skip the search when there are no notifiers.
+static REGISTERED: AtomicUsize = AtomicUsize::new(0);
+
+/// Signals the eventfd of every notifier that is registered for `address`.
+pub(crate) fn signal(address: usize) {
+    // Pairs with the fence in `AtomicNotifier::new`, so that either this notify observes the
+    // registration, or the event loop observes the value that was stored before the notify.
+    fence(Ordering::SeqCst);
+    if REGISTERED.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    for slot in &SLOTS {
+        if slot.address.load(Ordering::Relaxed) != address {
+            continue;
+        }
+
+        slot.busy.fetch_add(1, Ordering::SeqCst);
+        if slot.address.load(Ordering::SeqCst) == address {
+            let value = 1u64;
+            unsafe {
+                libc::write(
+                    slot.fd.load(Ordering::Relaxed),
+                    &value as *const _ as *const _,
+                    size_of::<u64>(),
+                );
+            }
+        }
+        slot.busy.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// Binds an atomic to an eventfd, so that event loops can react to notifies.
+///
+/// Once registered, every [`AtomicWait::notify_one`] or [`AtomicWait::notify_all`] call on the atomic
+/// also signals the eventfd, in addition to waking threads that are waiting on it. The eventfd is
+/// non-blocking, and may be registered with `epoll` or `mio` for readability. Like ordinary waiters,
+/// the event loop should load the atomic after each wakeup, and after creating the notifier, since
+/// notifies that happened earlier are not reported.
+///
+/// Notifies made by the primitives in [`sync`](crate::sync) on their internal atomics also signal
+/// the eventfd. Notifies through the [`uring`](crate::uring) submissions do not signal the eventfd.
+#[derive(Debug)]
+pub struct AtomicNotifier {
+    /// The eventfd that is signaled.
+    fd: OwnedFd,
+    /// The slot in which the eventfd is registered.
+    slot: &'static Slot,
+}
+
+impl AtomicNotifier {
+    /// Creates an eventfd, and registers it for notifies on `atomic`.
+    ///
+    /// At most 64 notifiers may exist at once.
+    pub fn new<A: AtomicWait>(atomic: &A) -> io::Result<Self> {
+        let raw = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        if raw < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+        let slot = SLOTS
+            .iter()
+            .find(|slot| {
+                slot.fd
+                    .compare_exchange(-1, raw, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+            })
+            .ok_or_else(|| io::Error::other("too many atomic notifiers"))?;
+
+        REGISTERED.fetch_add(1, Ordering::Relaxed);
+        slot.address
+            .store(atomic as *const A as usize, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
+
+        Ok(Self { fd, slot })
+    }
+
+    /// Clears the eventfd, returning whether it had been signaled since the last reset.
+    pub fn reset(&self) -> bool {
+        let mut value = 0u64;
+        let read = unsafe {
+            libc::read(
+                self.fd.as_raw_fd(),
+                &mut value as *mut _ as *mut _,
+                size_of::<u64>(),
+            )
+        };
+
+        read == size_of::<u64>() as isize
+    }
+}
+
+impl AsFd for AtomicNotifier {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for AtomicNotifier {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl Drop for AtomicNotifier {
+    fn drop(&mut self) {
+        self.slot.address.store(0, Ordering::SeqCst);
+        while self.slot.busy.load(Ordering::SeqCst) != 0 {
+            core::hint::spin_loop();
+        }
+
+        REGISTERED.fetch_sub(1, Ordering::Relaxed);
+        self.slot.fd.store(-1, Ordering::Release);
+    }
+}
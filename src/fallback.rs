@@ -1,46 +1,80 @@
 use std::{
-    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+    sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering},
     time::Duration,
 };
 
-use crate::{condvar_table, private::AtomicWaitImpl};
+use crate::{backend::WaitBackend, wait_table};
 
-impl AtomicWaitImpl for AtomicU32 {
-    type AtomicInner = u32;
+/// Waits using a table of OS synchronization primitives.
+pub struct Fallback;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
-        condvar_table::wait(
-            self as *const _ as *const _,
-            || self.load(Ordering::Acquire) == value,
+unsafe impl WaitBackend for Fallback {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, timeout: Option<Duration>) {
+        wait_table::wait(
+            atomic as *const _ as *const _,
+            || atomic.load(Ordering::Acquire) == value,
             timeout,
         );
     }
 
-    fn notify_all(&self) {
-        condvar_table::notify_all(self as *const _ as *const _);
+    fn notify_all32(&self, atomic: &AtomicU32) {
+        wait_table::notify_all(atomic as *const _ as *const _);
     }
 
-    fn notify_one(&self) {
-        condvar_table::notify_one(self as *const _ as *const _);
+    fn notify_one32(&self, atomic: &AtomicU32) {
+        wait_table::notify_one(atomic as *const _ as *const _);
     }
-}
-
-impl AtomicWaitImpl for AtomicU64 {
-    type AtomicInner = u64;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
-        condvar_table::wait(
-            self as *const _ as *const _,
-            || self.load(Ordering::Acquire) == value,
+    fn wait64(&self, atomic: &AtomicU64, value: u64, timeout: Option<Duration>) {
+        wait_table::wait(
+            atomic as *const _ as *const _,
+            || atomic.load(Ordering::Acquire) == value,
             timeout,
         );
     }
 
-    fn notify_all(&self) {
-        condvar_table::notify_all(self as *const _ as *const _);
+    fn notify_all64(&self, atomic: &AtomicU64) {
+        wait_table::notify_all(atomic as *const _ as *const _);
     }
 
-    fn notify_one(&self) {
-        condvar_table::notify_one(self as *const _ as *const _);
+    fn notify_one64(&self, atomic: &AtomicU64) {
+        wait_table::notify_one(atomic as *const _ as *const _);
     }
 }
+
+/// The number of entries in the wait table, if it is not configured.
+const DEFAULT_TABLE_SIZE: usize = 256;
+
+/// Set in [`TABLE_SIZE`] once the wait table has been allocated,
+/// after which its size can no longer change.
+const FROZEN: usize = 1 << (usize::BITS - 1);
+
+/// The number of entries in the wait table, which is always a power of two.
+static TABLE_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_TABLE_SIZE);
+
+/// Sets the number of entries in the wait table, which must be a power of two,
+/// or the default if zero is given. Returns `false` if the table was already allocated.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_table_size(size: usize) -> bool {
+    let size = if size == 0 { DEFAULT_TABLE_SIZE } else { size };
+    TABLE_SIZE
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+            (x & FROZEN == 0).then_some(size)
+        })
+        .is_ok()
+}
+
+/// Gets the number of entries with which to allocate the wait table,
+/// preventing it from being changed afterward.
+pub fn freeze_table_size() -> usize {
+    TABLE_SIZE.fetch_or(FROZEN, Ordering::Relaxed) & !FROZEN
+}
+
+/// Gets the index of the entry to use for `ptr`, in a wait table with `len` entries.
+pub fn index_for_ptr(ptr: *const (), len: usize) -> usize {
+    // Fibonacci hashing spreads neighbouring addresses across the table,
+    // and takes the top bits so that any power-of-two length works.
+    let hash = (ptr as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    hash.checked_shr(u64::BITS - len.trailing_zeros())
+        .unwrap_or(0) as usize
+}
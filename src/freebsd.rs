@@ -1,14 +1,15 @@
-use std::{
+use core::{
     sync::atomic::{AtomicU32, AtomicU64},
     time::Duration,
 };
 
-use crate::private::AtomicWaitImpl;
+use crate::backend::WaitBackend;
 
-impl AtomicWaitImpl for AtomicU32 {
-    type AtomicInner = u32;
+/// Waits using the `_umtx_op` syscall.
+pub struct Platform;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+unsafe impl WaitBackend for Platform {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, timeout: Option<Duration>) {
         unsafe {
             if let Some(time) = timeout {
                 let wait_timespec = libc::_umtx_time {
@@ -21,7 +22,7 @@ impl AtomicWaitImpl for AtomicU32 {
                 };
 
                 libc::_umtx_op(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     libc::UMTX_OP_WAIT_UINT_PRIVATE,
                     value as u64,
                     size_of::<libc::_umtx_time>() as *mut _,
@@ -29,45 +30,41 @@ impl AtomicWaitImpl for AtomicU32 {
                 );
             } else {
                 libc::_umtx_op(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     libc::UMTX_OP_WAIT_UINT_PRIVATE,
                     value as u64,
-                    std::ptr::null_mut(),
-                    std::ptr::null_mut(),
+                    core::ptr::null_mut(),
+                    core::ptr::null_mut(),
                 );
             }
         };
     }
 
-    fn notify_all(&self) {
+    fn notify_all32(&self, atomic: &AtomicU32) {
         unsafe {
             libc::_umtx_op(
-                self as *const _ as *mut _,
+                atomic as *const _ as *mut _,
                 libc::UMTX_OP_WAKE_PRIVATE,
                 i32::MAX as libc::c_ulong,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
             );
         };
     }
 
-    fn notify_one(&self) {
+    fn notify_one32(&self, atomic: &AtomicU32) {
         unsafe {
             libc::_umtx_op(
-                self as *const _ as *mut _,
+                atomic as *const _ as *mut _,
                 libc::UMTX_OP_WAKE_PRIVATE,
                 1 as libc::c_ulong,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
             );
         };
     }
-}
-
-impl AtomicWaitImpl for AtomicU64 {
-    type AtomicInner = u64;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+    fn wait64(&self, atomic: &AtomicU64, value: u64, timeout: Option<Duration>) {
         unsafe {
             if let Some(time) = timeout {
                 let wait_timespec = libc::_umtx_time {
@@ -80,7 +77,7 @@ impl AtomicWaitImpl for AtomicU64 {
                 };
 
                 libc::_umtx_op(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     libc::UMTX_OP_WAIT,
                     value,
                     size_of::<libc::_umtx_time>() as *mut _,
@@ -88,37 +85,41 @@ impl AtomicWaitImpl for AtomicU64 {
                 );
             } else {
                 libc::_umtx_op(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     libc::UMTX_OP_WAIT,
                     value,
-                    std::ptr::null_mut(),
-                    std::ptr::null_mut(),
+                    core::ptr::null_mut(),
+                    core::ptr::null_mut(),
                 );
             }
         };
     }
 
-    fn notify_all(&self) {
+    fn notify_all64(&self, atomic: &AtomicU64) {
         unsafe {
             libc::_umtx_op(
-                self as *const _ as *mut _,
+                atomic as *const _ as *mut _,
                 libc::UMTX_OP_WAKE_PRIVATE,
                 i32::MAX as libc::c_ulong,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
             );
         };
     }
 
-    fn notify_one(&self) {
+    fn notify_one64(&self, atomic: &AtomicU64) {
         unsafe {
             libc::_umtx_op(
-                self as *const _ as *mut _,
+                atomic as *const _ as *mut _,
                 libc::UMTX_OP_WAKE_PRIVATE,
                 1 as libc::c_ulong,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
             );
         };
     }
+
+    fn notify_is_signal_safe(&self) -> bool {
+        true
+    }
 }
@@ -1,13 +1,16 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(
     all(nightly, target_arch = "wasm32"),
     feature(stdarch_wasm_atomic_wait)
 )]
 
-use std::{
-    sync::atomic::{AtomicI32, AtomicI64, AtomicU32, AtomicU64},
+use core::{
+    sync::atomic::{AtomicI32, AtomicI64, AtomicU32, AtomicU64, Ordering},
     time::Duration,
 };
+#[cfg(feature = "std")]
+use std::time::Instant;
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 #[path = "linux.rs"]
@@ -29,6 +32,10 @@ mod platform;
 #[path = "wasm32.rs"]
 mod platform;
 
+#[cfg(all(target_arch = "wasm32", not(feature = "std")))]
+compile_error!("wait_on_address requires the `std` feature on wasm32");
+
+/// Uses the fallback on all other platforms.
 #[cfg(not(any(
     target_arch = "wasm32",
     target_os = "linux",
@@ -39,14 +46,97 @@ mod platform;
     target_os = "watchos",
     windows
 )))]
-#[path = "fallback.rs"]
-mod platform;
+mod platform {
+    #[cfg(feature = "std")]
+    pub use crate::fallback::Fallback as Platform;
+
+    #[cfg(not(feature = "std"))]
+    pub use crate::spin::Spin as Platform;
+}
+
+/// Raw system calls, which are used instead of `libc` wrappers
+/// so that the Linux backend works without `std`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod syscall;
+
+/// Implements atomic waits with a table, for platforms
+/// or operations without native support.
+#[cfg(feature = "std")]
+mod fallback;
+
+/// Implements atomic waits by spinning, for platforms without `std`.
+mod spin;
+
+/// Runtime selection of the wait implementation.
+pub mod backend;
+
+/// Priority-inheritance locks.
+#[cfg(any(target_os = "linux", target_os = "android", feature = "std"))]
+pub mod pi;
+
+/// Locks that are shared between processes, and survive the death of their owner.
+#[cfg(all(any(target_os = "linux", target_os = "android"), feature = "std"))]
+pub mod robust;
+
+/// Bridges notifies to eventfds, for event loops.
+#[cfg(all(any(target_os = "linux", target_os = "android"), feature = "eventfd"))]
+pub mod eventfd;
+
+/// Atomics whose waiters are hashed on a chosen NUMA node.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod numa;
+
+/// Submissions that wait on and wake atomics through `io_uring`.
+#[cfg(all(any(target_os = "linux", target_os = "android"), feature = "io-uring"))]
+pub mod uring;
+
+/// Synchronization primitives built on [`AtomicWait`].
+pub mod sync;
 
 /// A table of OS synchronization primitives for manually
 /// implementing futex functionality on unsupported platforms.
+#[cfg(feature = "std")]
 #[allow(unused)]
 mod condvar_table;
 
+/// A table of parked threads for manually implementing futex
+/// functionality without `std`'s `Mutex` and `Condvar`.
+#[cfg(feature = "thread-parker")]
+#[allow(unused)]
+mod parker_table;
+
+/// The table used by platforms that lack a native wait operation.
+#[cfg(all(feature = "std", not(feature = "thread-parker")))]
+use condvar_table as wait_table;
+
+/// The table used by platforms that lack a native wait operation.
+#[cfg(feature = "thread-parker")]
+use parker_table as wait_table;
+
+/// How closely a timed wait should match its timeout.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Precision {
+    /// Sleep in the kernel for the whole timeout. The wait may end
+    /// late by the timer slack and scheduling latency of the platform.
+    #[default]
+    Normal,
+    /// Sleep in the kernel until shortly before the deadline, and then spin until it,
+    /// which is accurate to tens of microseconds at the cost of CPU time.
+    ///
+    /// On Linux, lowering the timer slack of the thread with
+    /// `backend::set_timer_slack` shortens the spin.
+    High,
+}
+
+/// How long before the deadline a [`Precision::High`] wait stops sleeping and starts
+/// spinning. Windows timers have a resolution of a millisecond at best.
+#[cfg(feature = "std")]
+const SPIN_MARGIN: Duration = if cfg!(windows) {
+    Duration::from_millis(2)
+} else {
+    Duration::from_micros(500)
+};
+
 /// A type that supports atomic waits.
 pub trait AtomicWait: private::AtomicWaitImpl {
     /// If the value is `value`, wait until woken up.
@@ -66,6 +156,40 @@ pub trait AtomicWait: private::AtomicWaitImpl {
         private::AtomicWaitImpl::wait_timeout(self, value, Some(timeout));
     }
 
+    /// If the value is `value`, wait until timeout elapses
+    /// or notify is called, with the given `precision`.
+    ///
+    /// This function might also return spuriously,
+    /// without a corresponding wake operation.
+    #[cfg(feature = "std")]
+    fn wait_timeout_with_precision(
+        &self,
+        value: Self::AtomicInner,
+        timeout: Duration,
+        precision: Precision,
+    ) {
+        let deadline = match precision {
+            Precision::Normal => None,
+            Precision::High => Instant::now().checked_add(timeout),
+        };
+
+        let Some(deadline) = deadline else {
+            return private::AtomicWaitImpl::wait_timeout(self, value, Some(timeout));
+        };
+
+        if let Some(sleep) = timeout.checked_sub(SPIN_MARGIN) {
+            private::AtomicWaitImpl::wait_timeout(self, value, Some(sleep));
+            if Instant::now() < deadline - SPIN_MARGIN {
+                // Woken before the sleep ended, by a notify or spuriously.
+                return;
+            }
+        }
+
+        while private::AtomicWaitImpl::current_value(self) == value && Instant::now() < deadline {
+            core::hint::spin_loop();
+        }
+    }
+
     /// Wake one thread that is waiting on this atomic.
     fn notify_one(&self) {
         private::AtomicWaitImpl::notify_one(self);
@@ -75,6 +199,37 @@ pub trait AtomicWait: private::AtomicWaitImpl {
     fn notify_all(&self) {
         private::AtomicWaitImpl::notify_all(self);
     }
+
+    /// Wake one thread that is waiting on this atomic, if this can be done in
+    /// an async-signal-safe way. Returns whether the notify took place.
+    ///
+    /// This allows signal handlers to wake other threads. Notifies are signal-safe with
+    /// the platform backend on platforms with native support, and with the spin backend,
+    /// but not with the fallback, which takes locks. Custom backends opt in through
+    /// [`WaitBackend::notify_is_signal_safe`](backend::WaitBackend::notify_is_signal_safe).
+    /// Since choosing the backend reads the environment, this also returns `false` until
+    /// the backend has been chosen, by the first wait or notify or by [`backend::backend`].
+    fn notify_one_signal_safe(&self) -> bool {
+        let safe = backend::is_signal_safe();
+        if safe {
+            private::AtomicWaitImpl::notify_one(self);
+        }
+
+        safe
+    }
+
+    /// Wake all threads that are waiting on this atomic, if this can be done in
+    /// an async-signal-safe way. Returns whether the notify took place.
+    ///
+    /// See [`AtomicWait::notify_one_signal_safe`] for the backends that support this.
+    fn notify_all_signal_safe(&self) -> bool {
+        let safe = backend::is_signal_safe();
+        if safe {
+            private::AtomicWaitImpl::notify_all(self);
+        }
+
+        safe
+    }
 }
 
 impl AtomicWait for AtomicU32 {}
@@ -82,12 +237,64 @@ impl AtomicWait for AtomicU64 {}
 impl AtomicWait for AtomicI32 {}
 impl AtomicWait for AtomicI64 {}
 
+impl private::AtomicWaitImpl for AtomicU32 {
+    type AtomicInner = u32;
+
+    fn current_value(&self) -> Self::AtomicInner {
+        self.load(Ordering::Acquire)
+    }
+
+    fn notify_all(&self) {
+        backend::current().notify_all32(self);
+        #[cfg(all(any(target_os = "linux", target_os = "android"), feature = "eventfd"))]
+        eventfd::signal(self as *const _ as usize);
+    }
+
+    fn notify_one(&self) {
+        backend::current().notify_one32(self);
+        #[cfg(all(any(target_os = "linux", target_os = "android"), feature = "eventfd"))]
+        eventfd::signal(self as *const _ as usize);
+    }
+
+    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+        backend::current().wait32(self, value, timeout);
+    }
+}
+
+impl private::AtomicWaitImpl for AtomicU64 {
+    type AtomicInner = u64;
+
+    fn current_value(&self) -> Self::AtomicInner {
+        self.load(Ordering::Acquire)
+    }
+
+    fn notify_all(&self) {
+        backend::current().notify_all64(self);
+        #[cfg(all(any(target_os = "linux", target_os = "android"), feature = "eventfd"))]
+        eventfd::signal(self as *const _ as usize);
+    }
+
+    fn notify_one(&self) {
+        backend::current().notify_one64(self);
+        #[cfg(all(any(target_os = "linux", target_os = "android"), feature = "eventfd"))]
+        eventfd::signal(self as *const _ as usize);
+    }
+
+    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+        backend::current().wait64(self, value, timeout);
+    }
+}
+
 impl private::AtomicWaitImpl for AtomicI32 {
     type AtomicInner = i32;
 
+    fn current_value(&self) -> Self::AtomicInner {
+        self.load(Ordering::Acquire)
+    }
+
     fn notify_all(&self) {
         unsafe {
-            private::AtomicWaitImpl::notify_all(std::mem::transmute::<&AtomicI32, &AtomicU32>(
+            private::AtomicWaitImpl::notify_all(core::mem::transmute::<&AtomicI32, &AtomicU32>(
                 self,
             ));
         }
@@ -95,7 +302,7 @@ impl private::AtomicWaitImpl for AtomicI32 {
 
     fn notify_one(&self) {
         unsafe {
-            private::AtomicWaitImpl::notify_one(std::mem::transmute::<&AtomicI32, &AtomicU32>(
+            private::AtomicWaitImpl::notify_one(core::mem::transmute::<&AtomicI32, &AtomicU32>(
                 self,
             ));
         }
@@ -104,7 +311,7 @@ impl private::AtomicWaitImpl for AtomicI32 {
     fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
         unsafe {
             private::AtomicWaitImpl::wait_timeout(
-                std::mem::transmute::<&AtomicI32, &AtomicU32>(self),
+                core::mem::transmute::<&AtomicI32, &AtomicU32>(self),
                 value as u32,
                 timeout,
             );
@@ -115,9 +322,13 @@ impl private::AtomicWaitImpl for AtomicI32 {
 impl private::AtomicWaitImpl for AtomicI64 {
     type AtomicInner = i64;
 
+    fn current_value(&self) -> Self::AtomicInner {
+        self.load(Ordering::Acquire)
+    }
+
     fn notify_all(&self) {
         unsafe {
-            private::AtomicWaitImpl::notify_all(std::mem::transmute::<&AtomicI64, &AtomicU64>(
+            private::AtomicWaitImpl::notify_all(core::mem::transmute::<&AtomicI64, &AtomicU64>(
                 self,
             ));
         }
@@ -125,7 +336,7 @@ impl private::AtomicWaitImpl for AtomicI64 {
 
     fn notify_one(&self) {
         unsafe {
-            private::AtomicWaitImpl::notify_one(std::mem::transmute::<&AtomicI64, &AtomicU64>(
+            private::AtomicWaitImpl::notify_one(core::mem::transmute::<&AtomicI64, &AtomicU64>(
                 self,
             ));
         }
@@ -134,7 +345,7 @@ impl private::AtomicWaitImpl for AtomicI64 {
     fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
         unsafe {
             private::AtomicWaitImpl::wait_timeout(
-                std::mem::transmute::<&AtomicI64, &AtomicU64>(self),
+                core::mem::transmute::<&AtomicI64, &AtomicU64>(self),
                 value as u64,
                 timeout,
             );
@@ -144,12 +355,15 @@ impl private::AtomicWaitImpl for AtomicI64 {
 
 /// Private implementation details.
 mod private {
-    use std::time::Duration;
+    use core::time::Duration;
 
     /// A trait that cannot be implemented by other crates.
     pub trait AtomicWaitImpl {
         /// The underlying integer type for the atomic.
-        type AtomicInner;
+        type AtomicInner: Copy + PartialEq;
+
+        /// Loads the value of the atomic.
+        fn current_value(&self) -> Self::AtomicInner;
 
         /// Wake all threads that are waiting on this atomic.
         fn notify_all(&self);
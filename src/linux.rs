@@ -1,72 +1,245 @@
-use std::{
+use core::{
     sync::atomic::{AtomicU32, AtomicU64, Ordering},
     time::Duration,
 };
 
-use crate::{condvar_table, private::AtomicWaitImpl};
-
-impl AtomicWaitImpl for AtomicU32 {
-    type AtomicInner = u32;
-
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
-        unsafe {
-            let wait_timespec = timeout.map(|x| libc::timespec {
-                tv_sec: x.as_secs() as i64,
-                tv_nsec: x.subsec_nanos() as i64,
-            });
-
-            libc::syscall(
-                libc::SYS_futex,
-                self as *const _,
-                libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
-                value,
-                wait_timespec
-                    .as_ref()
-                    .map(|x| x as *const _)
-                    .unwrap_or(std::ptr::null()),
-            );
+use crate::{
+    backend::WaitBackend,
+    syscall::{deadline, futex, syscall6},
+};
+
+/// The number of sequence counters used for 64-bit waits.
+const BUCKET_COUNT: usize = 256;
+
+/// Sequence counters upon which 64-bit waiters sleep, since futexes
+/// are only 32 bits wide. Notifying an atomic increments its counter,
+/// so a waiter cannot miss a notify between checking the value and sleeping.
+static BUCKETS: [AtomicU32; BUCKET_COUNT] = [const { AtomicU32::new(0) }; BUCKET_COUNT];
+
+/// The `prctl` option that configures the private futex hash table of the process (Linux 6.16+).
+const PR_FUTEX_HASH: usize = 78;
+
+/// Sets the number of slots in the private futex hash table.
+const PR_FUTEX_HASH_SET_SLOTS: usize = 1;
+
+/// Gets the number of slots in the private futex hash table.
+const PR_FUTEX_HASH_GET_SLOTS: usize = 2;
+
+/// The `prctl` option that sets the timer slack of the calling thread.
+const PR_SET_TIMERSLACK: usize = 29;
+
+/// Waits using the `SYS_futex` syscall.
+pub struct Platform;
+
+unsafe impl WaitBackend for Platform {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, timeout: Option<Duration>) {
+        futex_wait(atomic, value, timeout);
+    }
+
+    fn notify_all32(&self, atomic: &AtomicU32) {
+        futex_wake(atomic, i32::MAX as u32);
+    }
+
+    fn notify_one32(&self, atomic: &AtomicU32) {
+        futex_wake(atomic, 1);
+    }
+
+    fn wait64(&self, atomic: &AtomicU64, value: u64, timeout: Option<Duration>) {
+        let bucket = bucket_for_ptr(atomic as *const _ as *const _);
+        let sequence = bucket.load(Ordering::Acquire);
+        if atomic.load(Ordering::Acquire) == value {
+            futex_wait(bucket, sequence, timeout);
         }
     }
 
-    fn notify_all(&self) {
-        unsafe {
-            libc::syscall(
-                libc::SYS_futex,
-                self as *const _,
-                libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
-                i32::MAX,
-            );
-        };
+    fn notify_all64(&self, atomic: &AtomicU64) {
+        let bucket = bucket_for_ptr(atomic as *const _ as *const _);
+        bucket.fetch_add(1, Ordering::Release);
+        futex_wake(bucket, i32::MAX as u32);
+    }
+
+    fn notify_one64(&self, atomic: &AtomicU64) {
+        // Other atomics may share the bucket, so waking a single
+        // thread could wake the wrong one.
+        self.notify_all64(atomic);
     }
 
-    fn notify_one(&self) {
-        unsafe {
-            libc::syscall(
-                libc::SYS_futex,
-                self as *const _,
-                libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
-                1i32,
-            );
-        };
+    fn notify_is_signal_safe(&self) -> bool {
+        // Notifies are raw `futex` syscalls, and atomic increments for 64-bit atomics.
+        true
     }
 }
 
-impl AtomicWaitImpl for AtomicU64 {
-    type AtomicInner = u64;
+/// If `atomic` holds `value`, sleeps until woken or until `timeout` elapses.
+fn futex_wait(atomic: &AtomicU32, value: u32, timeout: Option<Duration>) {
+    let wait_timespec = timeout.map(|x| libc::timespec {
+        tv_sec: x.as_secs().min(libc::time_t::MAX as u64) as libc::time_t,
+        tv_nsec: x.subsec_nanos() as _,
+    });
+
+    unsafe {
+        futex(
+            atomic,
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            value,
+            wait_timespec
+                .as_ref()
+                .map(|x| x as *const _)
+                .unwrap_or(core::ptr::null()),
+            core::ptr::null(),
+            0,
+        );
+    }
+}
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
-        condvar_table::wait(
-            self as *const _ as *const _,
-            || self.load(Ordering::Acquire) == value,
-            timeout,
+/// Wakes up to `count` threads sleeping on `atomic`.
+fn futex_wake(atomic: &AtomicU32, count: u32) {
+    unsafe {
+        futex(
+            atomic,
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            count,
+            core::ptr::null(),
+            core::ptr::null(),
+            0,
         );
     }
+}
+
+/// If `atomic` holds `value`, sleeps until woken by a notify whose mask
+/// intersects `mask`, or until `timeout` elapses.
+pub(crate) fn futex_wait_bitset(
+    atomic: &AtomicU32,
+    value: u32,
+    mask: u32,
+    timeout: Option<Duration>,
+) {
+    // Unlike `FUTEX_WAIT`, the timeout is an absolute time on the monotonic clock.
+    let deadline = timeout.map(|x| deadline(libc::CLOCK_MONOTONIC, x));
 
-    fn notify_all(&self) {
-        condvar_table::notify_all(self as *const _ as *const _);
+    unsafe {
+        futex(
+            atomic,
+            libc::FUTEX_WAIT_BITSET | libc::FUTEX_PRIVATE_FLAG,
+            value,
+            deadline
+                .as_ref()
+                .map(|x| x as *const _)
+                .unwrap_or(core::ptr::null()),
+            core::ptr::null(),
+            mask,
+        );
     }
+}
+
+/// Wakes up to `count` threads sleeping on `atomic` with masks
+/// that intersect `mask`. Returns the number of threads woken.
+pub(crate) fn futex_wake_bitset(atomic: &AtomicU32, mask: u32, count: u32) -> usize {
+    let woken = unsafe {
+        futex(
+            atomic,
+            libc::FUTEX_WAKE_BITSET | libc::FUTEX_PRIVATE_FLAG,
+            count,
+            core::ptr::null(),
+            core::ptr::null(),
+            mask,
+        )
+    };
 
-    fn notify_one(&self) {
-        condvar_table::notify_one(self as *const _ as *const _);
+    woken.max(0) as usize
+}
+
+/// If `atomic` holds `value`, wakes up to `wake` threads sleeping on it, and moves up to
+/// `requeue` others to sleep on `target` instead. Returns `false` if `atomic` held another value.
+pub(crate) fn futex_cmp_requeue(
+    atomic: &AtomicU32,
+    value: u32,
+    wake: u32,
+    requeue: u32,
+    target: *const AtomicU32,
+) -> bool {
+    unsafe {
+        // The number of threads to requeue is passed in place of the timeout.
+        futex(
+            atomic,
+            libc::FUTEX_CMP_REQUEUE | libc::FUTEX_PRIVATE_FLAG,
+            wake,
+            requeue as usize as *const _,
+            target,
+            value,
+        ) >= 0
     }
 }
+
+/// Gets the sequence counter to use for the given address.
+pub(crate) fn bucket_for_ptr(ptr: *const ()) -> &'static AtomicU32 {
+    let x_64 = ptr as u64;
+    let x_32 = (x_64 >> 32) as u32 ^ x_64 as u32;
+    let x_16 = (x_32 >> 16) as u16 ^ x_32 as u16;
+    &BUCKETS[((x_16 >> 8) as u8 ^ x_16 as u8) as usize]
+}
+
+/// The outcome of [`configure_private_futex_hash`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FutexHashResult {
+    /// Whether the kernel applied the setting.
+    pub kernel: bool,
+    /// Whether the table of the fallback backend was resized to match.
+    pub fallback: bool,
+}
+
+/// Gives the process its own futex hash table with `slots` entries, so that its waiters
+/// no longer share buckets with other processes, or returns to the global table if `slots`
+/// is zero. This requires Linux 6.16 or later.
+///
+/// Once the kernel has applied the setting, the table of the fallback backend is given the
+/// same number of entries, or its default size if `slots` is zero. It is allocated on its
+/// first use, and cannot be resized afterward, so this should be called before any waits
+/// or notifies. The result reports whether each table was configured.
+///
+/// # Panics
+///
+/// Panics if `slots` is not zero or a power of two greater than one.
+pub fn configure_private_futex_hash(slots: usize) -> FutexHashResult {
+    assert!(
+        slots == 0 || (slots > 1 && slots.is_power_of_two()),
+        "futex hash slots must be zero or a power of two greater than one"
+    );
+
+    let kernel = unsafe {
+        syscall6(
+            libc::SYS_prctl,
+            [PR_FUTEX_HASH, PR_FUTEX_HASH_SET_SLOTS, slots, 0, 0, 0],
+        ) == 0
+    };
+
+    #[cfg(feature = "std")]
+    let fallback = kernel && crate::fallback::set_table_size(slots);
+    #[cfg(not(feature = "std"))]
+    let fallback = false;
+
+    FutexHashResult { kernel, fallback }
+}
+
+/// Gets the number of entries in the private futex hash table of the process, which
+/// is zero if it uses the global table, or `None` if the kernel is older than Linux 6.16.
+pub fn private_futex_hash() -> Option<usize> {
+    let slots = unsafe {
+        syscall6(
+            libc::SYS_prctl,
+            [PR_FUTEX_HASH, PR_FUTEX_HASH_GET_SLOTS, 0, 0, 0, 0],
+        )
+    };
+
+    (slots >= 0).then_some(slots as usize)
+}
+
+/// Sets how late the kernel may end timed sleeps of the calling thread, so that it can
+/// coalesce wakeups, which is 50 microseconds by default. Returns whether it was applied.
+///
+/// Lowering the slack makes timed waits more accurate, at the cost of more wakeups.
+/// A slack of zero restores the default.
+pub fn set_timer_slack(slack: Duration) -> bool {
+    let nanos = slack.as_nanos().min(usize::MAX as u128) as usize;
+    unsafe { syscall6(libc::SYS_prctl, [PR_SET_TIMERSLACK, nanos, 0, 0, 0, 0]) == 0 }
+}
@@ -1,98 +1,99 @@
-use std::{
+use core::{
     sync::atomic::{AtomicU32, AtomicU64},
     time::Duration,
 };
 
-use crate::private::AtomicWaitImpl;
+use crate::backend::WaitBackend;
 
-impl AtomicWaitImpl for AtomicU32 {
-    type AtomicInner = u32;
+/// Waits using the `os_sync_wait_on_address` and `os_sync_wake_by_address` APIs.
+pub struct Platform;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+unsafe impl WaitBackend for Platform {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, timeout: Option<Duration>) {
         unsafe {
             if let Some(time) = timeout {
                 libc::os_sync_wait_on_address_with_timeout(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     value as u64,
-                    size_of::<Self>(),
+                    size_of::<AtomicU32>(),
                     libc::OS_SYNC_WAIT_ON_ADDRESS_NONE,
                     libc::CLOCK_MONOTONIC,
                     time.as_nanos().min(u64::MAX as u128) as u64,
                 );
             } else {
                 libc::os_sync_wait_on_address(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     value as u64,
-                    size_of::<Self>(),
+                    size_of::<AtomicU32>(),
                     libc::OS_SYNC_WAIT_ON_ADDRESS_NONE,
                 );
             }
         }
     }
 
-    fn notify_all(&self) {
+    fn notify_all32(&self, atomic: &AtomicU32) {
         unsafe {
             libc::os_sync_wake_by_address_all(
-                self as *const _ as *mut _,
-                size_of::<Self>(),
+                atomic as *const _ as *mut _,
+                size_of::<AtomicU32>(),
                 libc::OS_SYNC_WAKE_BY_ADDRESS_NONE,
             );
         };
     }
 
-    fn notify_one(&self) {
+    fn notify_one32(&self, atomic: &AtomicU32) {
         unsafe {
             libc::os_sync_wake_by_address_any(
-                self as *const _ as *mut _,
-                size_of::<Self>(),
+                atomic as *const _ as *mut _,
+                size_of::<AtomicU32>(),
                 libc::OS_SYNC_WAKE_BY_ADDRESS_NONE,
             );
         };
     }
-}
-
-impl AtomicWaitImpl for AtomicU64 {
-    type AtomicInner = u64;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+    fn wait64(&self, atomic: &AtomicU64, value: u64, timeout: Option<Duration>) {
         unsafe {
             if let Some(time) = timeout {
                 libc::os_sync_wait_on_address_with_timeout(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     value,
-                    size_of::<Self>(),
+                    size_of::<AtomicU64>(),
                     libc::OS_SYNC_WAIT_ON_ADDRESS_NONE,
                     libc::CLOCK_MONOTONIC,
                     time.as_nanos().min(u64::MAX as u128) as u64,
                 );
             } else {
                 libc::os_sync_wait_on_address(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     value,
-                    size_of::<Self>(),
+                    size_of::<AtomicU64>(),
                     libc::OS_SYNC_WAIT_ON_ADDRESS_NONE,
                 );
             }
         }
     }
 
-    fn notify_all(&self) {
+    fn notify_all64(&self, atomic: &AtomicU64) {
         unsafe {
             libc::os_sync_wake_by_address_all(
-                self as *const _ as *mut _,
-                size_of::<Self>(),
+                atomic as *const _ as *mut _,
+                size_of::<AtomicU64>(),
                 libc::OS_SYNC_WAKE_BY_ADDRESS_NONE,
             );
         };
     }
 
-    fn notify_one(&self) {
+    fn notify_one64(&self, atomic: &AtomicU64) {
         unsafe {
             libc::os_sync_wake_by_address_any(
-                self as *const _ as *mut _,
-                size_of::<Self>(),
+                atomic as *const _ as *mut _,
+                size_of::<AtomicU64>(),
                 libc::OS_SYNC_WAKE_BY_ADDRESS_NONE,
             );
         };
     }
+
+    fn notify_is_signal_safe(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,220 @@
+use core::{
+    fmt,
+    ops::Deref,
+    sync::atomic::{AtomicU8, AtomicU32, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    AtomicWait, backend,
+    private::AtomicWaitImpl,
+    syscall::{
+        FUTEX_BITSET_MATCH_ANY, FUTEX2_NUMA, FUTEX2_PRIVATE, FUTEX2_SIZE_U32, SYS_FUTEX_WAIT,
+        SYS_FUTEX_WAKE, deadline, syscall6,
+    },
+};
+
+/// The node ID which indicates that the kernel should choose the
+/// node of the first thread to wait on or wake an atomic.
+pub const NO_NODE: u32 = u32::MAX;
+
+/// The `futex2` flags used for every operation.
+const FLAGS: u32 = FUTEX2_SIZE_U32 | FUTEX2_NUMA | FUTEX2_PRIVATE;
+
+/// Marks that support for `FUTEX2_NUMA` has not been detected yet.
+const UNKNOWN: u8 = 0;
+
+/// Marks that the kernel supports `FUTEX2_NUMA`.
+const SUPPORTED: u8 = 1;
+
+/// Marks that the kernel does not support `FUTEX2_NUMA`.
+const UNSUPPORTED: u8 = 2;
+
+/// Whether the kernel supports `FUTEX2_NUMA`.
+static SUPPORT: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// Gets whether the kernel supports `FUTEX2_NUMA`. If it does not,
+/// [`NumaAtomicU32`] behaves like an ordinary [`AtomicU32`].
+pub fn is_supported() -> bool {
+    match SUPPORT.load(Ordering::Relaxed) {
+        SUPPORTED => true,
+        UNSUPPORTED => false,
+        _ => detect(),
+    }
+}
+
+/// Checks for `FUTEX2_NUMA` by waking no threads on a temporary atomic.
+#[cold]
+fn detect() -> bool {
+    let atomic = NumaAtomicU32::new(0);
+    let supported = futex_wake(&atomic, 0) >= 0;
+    SUPPORT.store(
+        if supported { SUPPORTED } else { UNSUPPORTED },
+        Ordering::Relaxed,
+    );
+    supported
+}
+
+/// Gets whether operations on the current thread should use `FUTEX2_NUMA`,
+/// or be routed to the backend like other atomics.
+fn use_numa() -> bool {
+    backend::uses_platform() && is_supported()
+}
+
+/// An [`AtomicU32`] whose waiters are hashed on a chosen NUMA node (Linux 6.16+).
+///
+/// The kernel keeps waiters in a hash table, which is shared by all of the nodes of the machine unless
+/// `FUTEX2_NUMA` is used. This type follows the kernel's ABI for that flag: the atomic is followed by a
+/// second word that holds the ID of the node whose table it uses. Placing the table on the node where the
+/// atomic is used avoids contention on hash buckets between sockets.
+///
+/// If the kernel lacks `FUTEX2_NUMA`, or another backend than [`Backend::Platform`] is in use,
+/// the node is ignored. Waits and notifies must go through this type rather than the [`AtomicU32`]
+/// that it dereferences to, since waiters hashed on a node cannot be woken through the shared table.
+///
+/// [`Backend::Platform`]: crate::backend::Backend::Platform
+#[repr(C, align(8))]
+pub struct NumaAtomicU32 {
+    /// The atomic value.
+    value: AtomicU32,
+    /// The ID of the node whose hash table is used, or [`NO_NODE`].
+    node: AtomicU32,
+}
+
+impl NumaAtomicU32 {
+    /// Creates an atomic that uses the node of the first thread to wait on or wake it.
+    pub const fn new(value: u32) -> Self {
+        Self::with_node(value, NO_NODE)
+    }
+
+    /// Creates an atomic that uses the hash table of `node`.
+    ///
+    /// Waits and notifies fail if `node` is not online,
+    /// in which case they behave as if they were spurious.
+    pub const fn with_node(value: u32, node: u32) -> Self {
+        Self {
+            value: AtomicU32::new(value),
+            node: AtomicU32::new(node),
+        }
+    }
+
+    /// Gets the node whose hash table is used, or `None`
+    /// if the kernel has not assigned one yet.
+    pub fn node(&self) -> Option<u32> {
+        Some(self.node.load(Ordering::Relaxed)).filter(|&x| x != NO_NODE)
+    }
+
+    /// Sets the node whose hash table is used. No threads can be waiting,
+    /// since the atomic is borrowed mutably.
+    pub fn set_node(&mut self, node: u32) {
+        *self.node.get_mut() = node;
+    }
+
+    /// Consumes the atomic, returning its value.
+    pub fn into_inner(self) -> u32 {
+        self.value.into_inner()
+    }
+}
+
+impl Default for NumaAtomicU32 {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Deref for NumaAtomicU32 {
+    type Target = AtomicU32;
+
+    fn deref(&self) -> &AtomicU32 {
+        &self.value
+    }
+}
+
+impl fmt::Debug for NumaAtomicU32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NumaAtomicU32")
+            .field("value", &self.value)
+            .field("node", &self.node())
+            .finish()
+    }
+}
+
+impl AtomicWait for NumaAtomicU32 {}
+
+impl AtomicWaitImpl for NumaAtomicU32 {
+    type AtomicInner = u32;
+
+    fn current_value(&self) -> Self::AtomicInner {
+        self.value.load(Ordering::Acquire)
+    }
+
+    fn notify_all(&self) {
+        if use_numa() {
+            futex_wake(self, i32::MAX);
+        } else {
+            backend::current().notify_all32(&self.value);
+        }
+
+        #[cfg(feature = "eventfd")]
+        crate::eventfd::signal(self as *const _ as usize);
+    }
+
+    fn notify_one(&self) {
+        if use_numa() {
+            futex_wake(self, 1);
+        } else {
+            backend::current().notify_one32(&self.value);
+        }
+
+        #[cfg(feature = "eventfd")]
+        crate::eventfd::signal(self as *const _ as usize);
+    }
+
+    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+        if !use_numa() {
+            backend::current().wait32(&self.value, value, timeout);
+            return;
+        }
+
+        // `futex_wait` takes a `__kernel_timespec`, which has 64-bit fields on every architecture.
+        #[allow(clippy::unnecessary_cast)]
+        let deadline = timeout.map(|x| {
+            let time = deadline(libc::CLOCK_MONOTONIC, x);
+            [time.tv_sec as i64, time.tv_nsec as i64]
+        });
+
+        unsafe {
+            syscall6(
+                SYS_FUTEX_WAIT,
+                [
+                    self as *const _ as usize,
+                    value as usize,
+                    FUTEX_BITSET_MATCH_ANY as usize,
+                    FLAGS as usize,
+                    deadline
+                        .as_ref()
+                        .map(|x| x as *const _ as usize)
+                        .unwrap_or(0),
+                    libc::CLOCK_MONOTONIC as usize,
+                ],
+            );
+        }
+    }
+}
+
+/// Wakes up to `count` threads sleeping on `atomic` with `FUTEX2_NUMA`.
+fn futex_wake(atomic: &NumaAtomicU32, count: i32) -> isize {
+    unsafe {
+        syscall6(
+            SYS_FUTEX_WAKE,
+            [
+                atomic as *const _ as usize,
+                FUTEX_BITSET_MATCH_ANY as usize,
+                count as usize,
+                FLAGS as usize,
+                0,
+                0,
+            ],
+        )
+    }
+}
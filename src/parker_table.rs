@@ -0,0 +1,243 @@
+use std::{
+    cell::{Cell, UnsafeCell},
+    hint::spin_loop,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, Thread},
+    time::{Duration, Instant},
+};
+
+use crate::fallback::{freeze_table_size, index_for_ptr};
+
+/// The number of times to spin before yielding while acquiring a bucket lock.
+const SPIN_LIMIT: u32 = 64;
+
+/// The table of waiter lists, which is allocated on first use.
+static TABLE: OnceLock<Table> = OnceLock::new();
+
+/// Puts the current thread to sleep if `condition` evaluates to `true`.
+/// The thread will be woken after `timeout` if it is provided.
+pub fn wait(ptr: *const (), condition: impl FnOnce() -> bool, timeout: Option<Duration>) {
+    let bucket = bucket_for_ptr(ptr);
+    let guard = bucket.lock();
+    if !condition() {
+        return;
+    }
+
+    let waiter = Waiter {
+        address: ptr,
+        thread: thread::current(),
+        notified: AtomicBool::new(false),
+        next: Cell::new(std::ptr::null()),
+    };
+
+    guard.push(&waiter);
+    drop(guard);
+
+    let deadline = timeout.and_then(|x| Instant::now().checked_add(x));
+    while !waiter.notified.load(Ordering::Acquire) {
+        if let Some(deadline) = deadline {
+            let now = Instant::now();
+            if deadline <= now {
+                break;
+            }
+
+            thread::park_timeout(deadline - now);
+        } else {
+            thread::park();
+        }
+    }
+
+    if !waiter.notified.load(Ordering::Acquire) {
+        let guard = bucket.lock();
+        if !waiter.notified.load(Ordering::Acquire) {
+            guard.remove(&waiter);
+        }
+    }
+}
+
+/// Wakes all threads waiting on `ptr`.
+pub fn notify_all(ptr: *const ()) {
+    if !ptr.is_null() {
+        let guard = bucket_for_ptr(ptr).lock();
+        while guard.wake(ptr) {}
+    }
+}
+
+/// Wakes at least one thread waiting on `ptr`.
+pub fn notify_one(ptr: *const ()) {
+    if !ptr.is_null() {
+        bucket_for_ptr(ptr).lock().wake(ptr);
+    }
+}
+
+/// Gets the bucket to use for the given address.
+fn bucket_for_ptr(ptr: *const ()) -> &'static Bucket {
+    #[cfg(unix)]
+    register_fork_handler();
+
+    let table = TABLE.get_or_init(new_table);
+
+    // Safety: the table is only ever mutated by `reinitialize_in_child`,
+    // which runs while the child process has a single thread.
+    let table = unsafe { &*table.0.get() };
+    &table[index_for_ptr(ptr, table.len())]
+}
+
+/// Allocates a table with the configured number of entries.
+fn new_table() -> Table {
+    Table(UnsafeCell::new(
+        (0..freeze_table_size()).map(|_| Bucket::DEFAULT).collect(),
+    ))
+}
+
+/// Arranges for [`reinitialize_in_child`] to run after every `fork`.
+#[cfg(unix)]
+fn register_fork_handler() {
+    static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+    if !REGISTERED.load(Ordering::Acquire) && !REGISTERED.swap(true, Ordering::AcqRel) {
+        unsafe {
+            libc::pthread_atfork(
+                Some(initialize_before_fork),
+                None,
+                Some(reinitialize_in_child),
+            );
+        }
+    }
+}
+
+/// Empties every bucket in a freshly-forked child process,
+/// since the threads in the lists only exist in the parent.
+#[cfg(unix)]
+extern "C" fn reinitialize_in_child() {
+    if let Some(table) = TABLE.get() {
+        // Entries are overwritten through a raw pointer, since shared
+        // references to them were handed out before the fork.
+        unsafe {
+            let buckets = &raw mut **table.0.get();
+            for i in 0..buckets.len() {
+                std::ptr::write(buckets.cast::<Bucket>().add(i), Bucket::DEFAULT);
+            }
+        }
+    }
+}
+
+/// Finishes allocating the table before a `fork`, so that the child
+/// never observes it midway through initialization by another thread.
+#[cfg(unix)]
+extern "C" fn initialize_before_fork() {
+    TABLE.get_or_init(new_table);
+}
+
+/// A thread that is sleeping until it is notified.
+struct Waiter {
+    /// The address upon which the thread is waiting.
+    pub address: *const (),
+    /// The handle used to unpark the thread.
+    pub thread: Thread,
+    /// Whether the waiter has been removed from its list by a notifier.
+    pub notified: AtomicBool,
+    /// The next waiter in the same bucket.
+    pub next: Cell<*const Waiter>,
+}
+
+/// A spinlock-protected list of waiters.
+struct Bucket {
+    /// Whether the bucket is currently locked.
+    pub locked: AtomicBool,
+    /// The oldest waiter in the bucket.
+    pub head: Cell<*const Waiter>,
+}
+
+impl Bucket {
+    /// The starting value for a bucket.
+    #[allow(clippy::declare_interior_mutable_const)]
+    pub const DEFAULT: Self = Self {
+        locked: AtomicBool::new(false),
+        head: Cell::new(std::ptr::null()),
+    };
+
+    /// Locks the bucket, spinning and then yielding until it is available.
+    pub fn lock(&self) -> BucketGuard<'_> {
+        let mut spins = 0;
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            if spins < SPIN_LIMIT {
+                spins += 1;
+                spin_loop();
+            } else {
+                thread::yield_now();
+            }
+        }
+
+        BucketGuard(self)
+    }
+}
+
+/// Provides access to the list of a locked bucket.
+struct BucketGuard<'a>(&'a Bucket);
+
+impl BucketGuard<'_> {
+    /// Adds `waiter` to the end of the list.
+    pub fn push(&self, waiter: &Waiter) {
+        let mut link = &self.0.head;
+        while let Some(next) = unsafe { link.get().as_ref() } {
+            link = &next.next;
+        }
+
+        link.set(waiter);
+    }
+
+    /// Removes `waiter` from the list, if it is present.
+    pub fn remove(&self, waiter: &Waiter) {
+        let mut link = &self.0.head;
+        while let Some(next) = unsafe { link.get().as_ref() } {
+            if std::ptr::eq(next, waiter) {
+                link.set(next.next.get());
+                return;
+            }
+
+            link = &next.next;
+        }
+    }
+
+    /// Removes and unparks the oldest thread waiting on `ptr`.
+    /// Returns whether a thread was found.
+    pub fn wake(&self, ptr: *const ()) -> bool {
+        let mut link = &self.0.head;
+        while let Some(next) = unsafe { link.get().as_ref() } {
+            if next.address == ptr {
+                link.set(next.next.get());
+
+                // The waiter may return as soon as it observes the flag,
+                // so its thread handle must be taken beforehand.
+                let thread = next.thread.clone();
+                next.notified.store(true, Ordering::Release);
+                thread.unpark();
+                return true;
+            }
+
+            link = &next.next;
+        }
+
+        false
+    }
+}
+
+impl Drop for BucketGuard<'_> {
+    fn drop(&mut self) {
+        self.0.locked.store(false, Ordering::Release);
+    }
+}
+
+/// The set of all buckets.
+struct Table(UnsafeCell<Box<[Bucket]>>);
+
+unsafe impl Send for Table {}
+unsafe impl Sync for Table {}
@@ -0,0 +1,339 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+/// Set in the lock word when other threads are waiting for it.
+pub const WAITERS: u32 = 0x8000_0000;
+
+/// The bits of the lock word that hold the ID of the owning thread.
+pub const OWNER_MASK: u32 = 0x3fff_ffff;
+
+/// Acquires the priority-inheritance lock stored in `word`,
+/// waiting at most `timeout` if one is given.
+/// Returns whether the lock was acquired.
+///
+/// The word follows the kernel's protocol for PI futexes: it is `0` when
+/// unlocked, and otherwise holds the ID of the owning thread in the bits of
+/// [`OWNER_MASK`], along with the [`WAITERS`] bit while other threads wait.
+/// On Linux, a thread that blocks here lends its scheduling priority to the
+/// owner until the lock is released, which prevents priority inversion under
+/// real-time policies like `SCHED_FIFO`. Other platforms use the same protocol,
+/// but without priority inheritance.
+///
+/// # Panics
+///
+/// Panics if the calling thread already holds the lock.
+pub fn pi_lock(word: &AtomicU32, timeout: Option<Duration>) -> bool {
+    let id = imp::thread_id();
+    word.compare_exchange(0, id, Ordering::Acquire, Ordering::Relaxed)
+        .is_ok()
+        || imp::lock_contended(word, id, timeout)
+}
+
+/// Attempts to acquire the priority-inheritance lock stored in `word`
+/// without blocking. Returns whether the lock was acquired.
+pub fn pi_trylock(word: &AtomicU32) -> bool {
+    word.compare_exchange(0, imp::thread_id(), Ordering::Acquire, Ordering::Relaxed)
+        .is_ok()
+}
+
+/// Releases the priority-inheritance lock stored in `word`,
+/// handing it to the highest-priority waiter if there is one.
+///
+/// The lock must be held by the calling thread.
+pub fn pi_unlock(word: &AtomicU32) {
+    if word
+        .compare_exchange(imp::thread_id(), 0, Ordering::Release, Ordering::Relaxed)
+        .is_err()
+    {
+        imp::unlock_contended(word);
+    }
+}
+
+/// The kernel's priority-inheritance futexes.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod imp {
+    use core::{
+        sync::atomic::{AtomicBool, AtomicU32, Ordering},
+        time::Duration,
+    };
+
+    use crate::syscall::{FUTEX_LOCK_PI2, deadline, futex, gettid, remaining};
+
+    /// Whether the kernel predates `FUTEX_LOCK_PI2`.
+    static LOCK_PI2_UNSUPPORTED: AtomicBool = AtomicBool::new(false);
+
+    /// Gets the ID that marks the calling thread as the owner of a lock.
+    pub fn thread_id() -> u32 {
+        gettid()
+    }
+
+    /// Blocks in the kernel until the lock is handed to this thread.
+    pub fn lock_contended(word: &AtomicU32, _: u32, timeout: Option<Duration>) -> bool {
+        // The deadline is fixed up front, so that retries do not extend the wait.
+        let monotonic = timeout.map(|x| deadline(libc::CLOCK_MONOTONIC, x));
+        loop {
+            // `FUTEX_LOCK_PI2` uses the monotonic clock, while the
+            // original operation is limited to the realtime clock.
+            let (op, time) = if LOCK_PI2_UNSUPPORTED.load(Ordering::Relaxed) {
+                let realtime = monotonic
+                    .map(|x| deadline(libc::CLOCK_REALTIME, remaining(libc::CLOCK_MONOTONIC, &x)));
+                (libc::FUTEX_LOCK_PI, realtime)
+            } else {
+                (FUTEX_LOCK_PI2, monotonic)
+            };
+
+            let result = unsafe {
+                futex(
+                    word,
+                    op | libc::FUTEX_PRIVATE_FLAG,
+                    0,
+                    time.as_ref()
+                        .map(|x| x as *const _)
+                        .unwrap_or(core::ptr::null()),
+                    core::ptr::null(),
+                    0,
+                )
+            };
+
+            match -result as i32 {
+                0 => return true,
+                libc::ETIMEDOUT => return false,
+                libc::EINTR | libc::EAGAIN => {}
+                libc::ENOSYS if op == FUTEX_LOCK_PI2 => {
+                    LOCK_PI2_UNSUPPORTED.store(true, Ordering::Relaxed)
+                }
+                libc::EDEADLK => panic!("Attempted to lock a PI lock that is already held"),
+                x => panic!("Failed to lock PI futex (errno {x})"),
+            }
+        }
+    }
+
+    /// Asks the kernel to hand the lock to the highest-priority waiter.
+    pub fn unlock_contended(word: &AtomicU32) {
+        unsafe {
+            futex(
+                word,
+                libc::FUTEX_UNLOCK_PI | libc::FUTEX_PRIVATE_FLAG,
+                0,
+                core::ptr::null(),
+                core::ptr::null(),
+                0,
+            );
+        }
+    }
+}
+
+/// An emulation of the PI futex protocol with [`AtomicWait`](crate::AtomicWait),
+/// which provides mutual exclusion but not priority inheritance.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod imp {
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        time::{Duration, Instant},
+    };
+
+    use super::{OWNER_MASK, WAITERS};
+    use crate::AtomicWait;
+
+    /// Gets the ID that marks the calling thread as the owner of a lock.
+    pub fn thread_id() -> u32 {
+        static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+        std::thread_local! {
+            // Zero marks the lock as unlocked, so it is skipped if the IDs wrap.
+            static ID: u32 = loop {
+                let id = NEXT_ID.fetch_add(1, Ordering::Relaxed) & OWNER_MASK;
+                if id != 0 {
+                    break id;
+                }
+            };
+        }
+
+        ID.with(|x| *x)
+    }
+
+    /// Marks the lock as contended and waits until it is released.
+    pub fn lock_contended(word: &AtomicU32, id: u32, timeout: Option<Duration>) -> bool {
+        let deadline = timeout.and_then(|x| Instant::now().checked_add(x));
+        let mut state = word.load(Ordering::Relaxed);
+        loop {
+            if state & OWNER_MASK == 0 {
+                // Other threads may still be waiting, so the lock
+                // must stay marked as contended.
+                match word.compare_exchange_weak(
+                    state,
+                    id | WAITERS,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return true,
+                    Err(x) => state = x,
+                }
+
+                continue;
+            }
+
+            if (state & OWNER_MASK) == id {
+                panic!("Attempted to lock a PI lock that is already held");
+            }
+
+            if state & WAITERS == 0 {
+                if let Err(x) = word.compare_exchange_weak(
+                    state,
+                    state | WAITERS,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    state = x;
+                    continue;
+                }
+
+                state |= WAITERS;
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if deadline <= now {
+                        return false;
+                    }
+
+                    word.wait_timeout(state, deadline - now);
+                }
+                None => word.wait(state),
+            }
+
+            state = word.load(Ordering::Relaxed);
+        }
+    }
+
+    /// Releases the lock and wakes a waiter.
+    pub fn unlock_contended(word: &AtomicU32) {
+        word.store(0, Ordering::Release);
+        word.notify_one();
+    }
+}
+
+/// A mutual exclusion lock that uses priority inheritance where the platform supports it.
+///
+/// See [`pi_lock`] for details about the lock protocol.
+pub struct PiMutex<T: ?Sized> {
+    /// The PI lock word.
+    word: AtomicU32,
+    /// The protected data.
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for PiMutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for PiMutex<T> {}
+
+impl<T> PiMutex<T> {
+    /// Creates a new, unlocked mutex.
+    pub const fn new(value: T) -> Self {
+        Self {
+            word: AtomicU32::new(0),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the mutex, returning the protected data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> PiMutex<T> {
+    /// Acquires the mutex, blocking until it is available.
+    pub fn lock(&self) -> PiMutexGuard<'_, T> {
+        pi_lock(&self.word, None);
+        PiMutexGuard::new(self)
+    }
+
+    /// Attempts to acquire the mutex without blocking.
+    pub fn try_lock(&self) -> Option<PiMutexGuard<'_, T>> {
+        pi_trylock(&self.word).then(|| PiMutexGuard::new(self))
+    }
+
+    /// Attempts to acquire the mutex, blocking for at most `timeout`.
+    pub fn try_lock_for(&self, timeout: Duration) -> Option<PiMutexGuard<'_, T>> {
+        pi_lock(&self.word, Some(timeout)).then(|| PiMutexGuard::new(self))
+    }
+
+    /// Gets a mutable reference to the protected data.
+    /// No locking is needed, since the mutex is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<T: Default> Default for PiMutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for PiMutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("PiMutex");
+        match self.try_lock() {
+            Some(guard) => d.field("data", &&*guard),
+            None => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish_non_exhaustive()
+    }
+}
+
+/// Provides access to the data of a locked [`PiMutex`], and unlocks it when dropped.
+///
+/// The guard cannot be sent to other threads, because
+/// the lock must be released by the thread that owns it.
+pub struct PiMutexGuard<'a, T: ?Sized> {
+    /// The locked mutex.
+    mutex: &'a PiMutex<T>,
+    /// Prevents the guard from being sent to other threads.
+    marker: PhantomData<*const ()>,
+}
+
+impl<'a, T: ?Sized> PiMutexGuard<'a, T> {
+    /// Creates a guard for a mutex that the current thread has locked.
+    fn new(mutex: &'a PiMutex<T>) -> Self {
+        Self {
+            mutex,
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for PiMutexGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for PiMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for PiMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for PiMutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> Drop for PiMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        pi_unlock(&self.mutex.word);
+    }
+}
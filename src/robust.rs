@@ -0,0 +1,444 @@
+use core::{
+    cell::{Cell, UnsafeCell},
+    fmt,
+    marker::PhantomData,
+    mem::offset_of,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU32, Ordering, compiler_fence},
+};
+
+use crate::syscall::{fork_generation, futex, gettid, syscall6};
+
+/// Set in the lock word when other threads are waiting for it.
+const WAITERS: u32 = 0x8000_0000;
+
+/// Set in the lock word by the kernel when its owner died while holding it.
+const OWNER_DIED: u32 = 0x4000_0000;
+
+/// The bits of the lock word that hold the owner's thread ID.
+const TID_MASK: u32 = 0x3fff_ffff;
+
+/// The list of robust locks held by a thread, as defined by the kernel
+/// (`struct robust_list_head`). When the thread exits, the kernel marks every
+/// lock in the list with [`OWNER_DIED`] and wakes a waiter.
+#[repr(C)]
+struct RobustListHead {
+    /// The first held lock, or the head itself when the list is empty.
+    list: Cell<*const ()>,
+    /// The offset from each list entry to its lock word.
+    futex_offset: isize,
+    /// A lock that is being acquired or released, which the
+    /// kernel checks in case the thread dies halfway through.
+    list_op_pending: Cell<*const ()>,
+}
+
+std::thread_local! {
+    /// The robust list of the current thread.
+    static HEAD: RobustListHead = const {
+        RobustListHead {
+            list: Cell::new(core::ptr::null()),
+            futex_offset: offset_of!(RobustMutex<()>, word) as isize,
+            list_op_pending: Cell::new(core::ptr::null()),
+        }
+    };
+
+    /// The fork generation in which [`HEAD`] was registered with the
+    /// kernel, since registrations are not inherited by child processes.
+    static REGISTERED: Cell<u32> = const { Cell::new(u32::MAX) };
+
+    /// Whether the thread called [`take_over_robust_list`].
+    static TAKEN_OVER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Allows [`RobustMutex`]es to register their robust list for the current thread,
+/// replacing any list that was previously registered for it.
+///
+/// The kernel only tracks one robust list per thread, and `glibc` registers one
+/// for every thread that it creates, which it uses for robust `pthread_mutex_t`s.
+/// Once it is replaced, robust `pthread_mutex_t`s held by the thread, including
+/// those used internally by other libraries, are no longer released by the
+/// kernel when the thread exits. This must therefore only be called on threads
+/// that never hold such mutexes.
+pub fn take_over_robust_list() {
+    TAKEN_OVER.set(true);
+}
+
+/// Runs `f` with the robust list of the current thread,
+/// registering it with the kernel if necessary.
+///
+/// # Panics
+///
+/// Panics if another robust list is registered for the thread,
+/// and it has not called [`take_over_robust_list`].
+fn with_head<R>(f: impl FnOnce(&RobustListHead) -> R) -> R {
+    HEAD.with(|head| {
+        let generation = fork_generation();
+        if REGISTERED.get() != generation {
+            let current = registered_list();
+            assert!(
+                current.is_null() || core::ptr::eq(current, head) || TAKEN_OVER.get(),
+                "Another robust list is registered for this thread; \
+                call `take_over_robust_list` to replace it"
+            );
+
+            if head.list.get().is_null() {
+                head.list.set(head as *const _ as *const ());
+            }
+
+            unsafe {
+                syscall6(
+                    libc::SYS_set_robust_list,
+                    [
+                        head as *const _ as usize,
+                        size_of::<RobustListHead>(),
+                        0,
+                        0,
+                        0,
+                        0,
+                    ],
+                );
+            }
+
+            REGISTERED.set(generation);
+        }
+
+        f(head)
+    })
+}
+
+/// Gets the robust list that is registered with the kernel for the current thread,
+/// or null if there is none.
+fn registered_list() -> *const RobustListHead {
+    let mut head = core::ptr::null::<RobustListHead>();
+    let mut len = 0usize;
+    let result = unsafe {
+        syscall6(
+            libc::SYS_get_robust_list,
+            [
+                0,
+                &mut head as *mut _ as usize,
+                &mut len as *mut _ as usize,
+                0,
+                0,
+                0,
+            ],
+        )
+    };
+
+    if result == 0 { head } else { core::ptr::null() }
+}
+
+/// A mutual exclusion lock that can be shared between processes, and which
+/// is released by the kernel if its owner dies while holding it.
+///
+/// The mutex is meant to be placed in memory that is shared between processes,
+/// like a `MAP_SHARED` mapping, by writing the result of [`RobustMutex::new`]
+/// into it. The protected data must therefore not contain pointers that are
+/// only valid in one process.
+///
+/// When the owning thread or process exits without unlocking, the next call to
+/// [`RobustMutex::lock`] acquires the mutex and returns [`OwnerDied`], so that
+/// the caller can restore the protected data to a consistent state.
+///
+/// The robust list of each locking thread is registered with `set_robust_list`.
+/// Since the kernel only tracks one list per thread, threads which already have a
+/// list, like every thread created by `glibc`, must first opt in to replacing it
+/// with [`take_over_robust_list`].
+#[repr(C)]
+pub struct RobustMutex<T: ?Sized> {
+    /// The link in the robust list of the owning thread.
+    next: Cell<*const ()>,
+    /// The lock word, following the kernel's protocol for robust futexes.
+    word: AtomicU32,
+    /// The protected data.
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for RobustMutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for RobustMutex<T> {}
+
+impl<T> RobustMutex<T> {
+    /// Creates a new, unlocked mutex.
+    pub const fn new(value: T) -> Self {
+        Self {
+            next: Cell::new(core::ptr::null()),
+            word: AtomicU32::new(0),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the mutex, returning the protected data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> RobustMutex<T> {
+    /// Acquires the mutex, blocking until it is available.
+    ///
+    /// Returns [`OwnerDied`] if the previous owner exited while holding the mutex.
+    /// The mutex is still acquired in that case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another robust list is registered for the current thread,
+    /// and it has not called [`take_over_robust_list`].
+    pub fn lock(&self) -> Result<RobustMutexGuard<'_, T>, OwnerDied<RobustMutexGuard<'_, T>>> {
+        with_head(|head| {
+            let tid = gettid();
+            head.list_op_pending.set(self.entry());
+            compiler_fence(Ordering::SeqCst);
+
+            let mut waited = false;
+            let mut state = self.word.load(Ordering::Relaxed);
+            loop {
+                if state & TID_MASK == 0 {
+                    // Threads that have waited cannot know whether others are
+                    // still waiting, so they keep the lock marked as contended.
+                    let locked = tid | if waited { WAITERS } else { state & WAITERS };
+                    match self.word.compare_exchange_weak(
+                        state,
+                        locked,
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => break,
+                        Err(x) => state = x,
+                    }
+
+                    continue;
+                }
+
+                if state & WAITERS == 0 {
+                    if let Err(x) = self.word.compare_exchange_weak(
+                        state,
+                        state | WAITERS,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ) {
+                        state = x;
+                        continue;
+                    }
+
+                    state |= WAITERS;
+                }
+
+                unsafe {
+                    futex(
+                        &self.word,
+                        libc::FUTEX_WAIT,
+                        state,
+                        core::ptr::null(),
+                        core::ptr::null(),
+                        0,
+                    );
+                }
+
+                waited = true;
+                state = self.word.load(Ordering::Relaxed);
+            }
+
+            self.enqueue(head);
+            let guard = RobustMutexGuard::new(self);
+            if state & OWNER_DIED == 0 {
+                Ok(guard)
+            } else {
+                Err(OwnerDied(guard))
+            }
+        })
+    }
+
+    /// Attempts to acquire the mutex without blocking.
+    ///
+    /// Returns `None` if the mutex is held by a living owner,
+    /// and [`OwnerDied`] if the previous owner exited while holding it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another robust list is registered for the current thread,
+    /// and it has not called [`take_over_robust_list`].
+    #[allow(clippy::type_complexity)]
+    pub fn try_lock(
+        &self,
+    ) -> Option<Result<RobustMutexGuard<'_, T>, OwnerDied<RobustMutexGuard<'_, T>>>> {
+        with_head(|head| {
+            let tid = gettid();
+            head.list_op_pending.set(self.entry());
+            compiler_fence(Ordering::SeqCst);
+
+            let state = self.word.load(Ordering::Relaxed);
+            if state & TID_MASK != 0
+                || self
+                    .word
+                    .compare_exchange(
+                        state,
+                        tid | (state & WAITERS),
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    )
+                    .is_err()
+            {
+                head.list_op_pending.set(core::ptr::null());
+                return None;
+            }
+
+            self.enqueue(head);
+            let guard = RobustMutexGuard::new(self);
+            Some(if state & OWNER_DIED == 0 {
+                Ok(guard)
+            } else {
+                Err(OwnerDied(guard))
+            })
+        })
+    }
+
+    /// Gets a mutable reference to the protected data.
+    /// No locking is needed, since the mutex is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Gets the address that represents this mutex in robust lists.
+    fn entry(&self) -> *const () {
+        &self.next as *const _ as *const ()
+    }
+
+    /// Adds the newly-acquired mutex to the front of the robust list.
+    fn enqueue(&self, head: &RobustListHead) {
+        self.next.set(head.list.get());
+        compiler_fence(Ordering::SeqCst);
+        head.list.set(self.entry());
+        compiler_fence(Ordering::SeqCst);
+        head.list_op_pending.set(core::ptr::null());
+    }
+
+    /// Releases the mutex, removing it from the robust list of the current thread.
+    fn unlock(&self) {
+        with_head(|head| {
+            head.list_op_pending.set(self.entry());
+            compiler_fence(Ordering::SeqCst);
+
+            let mut link = &head.list;
+            while link.get() != self.entry() {
+                // Safety: every entry in the list is the `next` field of a held mutex.
+                link = unsafe { &*(link.get() as *const Cell<*const ()>) };
+            }
+            link.set(self.next.get());
+            compiler_fence(Ordering::SeqCst);
+
+            if self.word.swap(0, Ordering::Release) & WAITERS != 0 {
+                unsafe {
+                    futex(
+                        &self.word,
+                        libc::FUTEX_WAKE,
+                        1,
+                        core::ptr::null(),
+                        core::ptr::null(),
+                        0,
+                    );
+                }
+            }
+
+            compiler_fence(Ordering::SeqCst);
+            head.list_op_pending.set(core::ptr::null());
+        });
+    }
+}
+
+impl<T: Default> Default for RobustMutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RobustMutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RobustMutex").finish_non_exhaustive()
+    }
+}
+
+/// Provides access to the data of a locked [`RobustMutex`], and unlocks it when dropped.
+///
+/// The guard cannot be sent to other threads, because the
+/// mutex is recorded in the robust list of the owning thread.
+pub struct RobustMutexGuard<'a, T: ?Sized> {
+    /// The locked mutex.
+    mutex: &'a RobustMutex<T>,
+    /// Prevents the guard from being sent to other threads.
+    marker: PhantomData<*const ()>,
+}
+
+impl<'a, T: ?Sized> RobustMutexGuard<'a, T> {
+    /// Creates a guard for a mutex that the current thread has locked.
+    fn new(mutex: &'a RobustMutex<T>) -> Self {
+        Self {
+            mutex,
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for RobustMutexGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for RobustMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RobustMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RobustMutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> Drop for RobustMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+/// Indicates that the previous owner of a [`RobustMutex`] exited while holding it.
+///
+/// The mutex has been acquired regardless, and the guard may be recovered with
+/// [`OwnerDied::into_inner`] once the protected data has been made consistent.
+pub struct OwnerDied<G>(G);
+
+impl<G> OwnerDied<G> {
+    /// Consumes the error, returning the guard of the acquired mutex.
+    pub fn into_inner(self) -> G {
+        self.0
+    }
+
+    /// Gets a reference to the guard of the acquired mutex.
+    pub fn get_ref(&self) -> &G {
+        &self.0
+    }
+
+    /// Gets a mutable reference to the guard of the acquired mutex.
+    pub fn get_mut(&mut self) -> &mut G {
+        &mut self.0
+    }
+}
+
+impl<G> fmt::Debug for OwnerDied<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnerDied").finish_non_exhaustive()
+    }
+}
+
+impl<G> fmt::Display for OwnerDied<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the previous owner of the mutex died while holding it")
+    }
+}
+
+impl<G> std::error::Error for OwnerDied<G> {}
@@ -0,0 +1,103 @@
+use core::{
+    hint::spin_loop,
+    sync::atomic::{AtomicPtr, AtomicU32, AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::backend::WaitBackend;
+
+/// The number of times to check the value before calling the idle hook.
+const SPIN_LIMIT: u32 = 100;
+
+/// The function called when a wait has spun without observing a change.
+static IDLE_HOOK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// The function called on every notify.
+static WAKE_HOOK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Waits by spinning, without any support from the OS.
+///
+/// A wait checks the value a bounded number of times, and then calls the idle
+/// hook once before returning spuriously. Timeouts are therefore not measured,
+/// but a wait never outlasts a single call to the idle hook. Notifies call the
+/// wake hook, or do nothing if none is registered.
+pub struct Spin;
+
+unsafe impl WaitBackend for Spin {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, _: Option<Duration>) {
+        spin_until(|| atomic.load(Ordering::Acquire) != value);
+    }
+
+    fn notify_all32(&self, _: &AtomicU32) {
+        call_hook(&WAKE_HOOK);
+    }
+
+    fn notify_one32(&self, _: &AtomicU32) {
+        call_hook(&WAKE_HOOK);
+    }
+
+    fn wait64(&self, atomic: &AtomicU64, value: u64, _: Option<Duration>) {
+        spin_until(|| atomic.load(Ordering::Acquire) != value);
+    }
+
+    fn notify_all64(&self, _: &AtomicU64) {
+        call_hook(&WAKE_HOOK);
+    }
+
+    fn notify_one64(&self, _: &AtomicU64) {
+        call_hook(&WAKE_HOOK);
+    }
+
+    fn notify_is_signal_safe(&self) -> bool {
+        // Notifies only call the wake hook, which must be signal-safe itself.
+        true
+    }
+}
+
+/// Sets the function that [`Backend::Spin`](crate::backend::Backend::Spin) calls
+/// when a wait has spun for a while without the value changing.
+///
+/// This is typically an instruction that waits for an event or interrupt,
+/// like `wfe` or `wfi`, or a yield to the scheduler of an RTOS. The hook may
+/// return at any time, since waits are allowed to return spuriously.
+pub fn set_idle_hook(hook: Option<fn()>) {
+    IDLE_HOOK.store(
+        hook.map_or(core::ptr::null_mut(), |x| x as *mut ()),
+        Ordering::Release,
+    );
+}
+
+/// Sets the function that [`Backend::Spin`](crate::backend::Backend::Spin)
+/// calls whenever an atomic is notified.
+///
+/// This is typically the counterpart of the idle hook, like `sev`,
+/// or a signal to tasks that are blocked in the RTOS.
+pub fn set_wake_hook(hook: Option<fn()>) {
+    WAKE_HOOK.store(
+        hook.map_or(core::ptr::null_mut(), |x| x as *mut ()),
+        Ordering::Release,
+    );
+}
+
+/// Spins until `done` returns `true`, calling the idle hook
+/// if this does not happen within [`SPIN_LIMIT`] checks.
+fn spin_until(done: impl Fn() -> bool) {
+    for _ in 0..SPIN_LIMIT {
+        if done() {
+            return;
+        }
+
+        spin_loop();
+    }
+
+    call_hook(&IDLE_HOOK);
+}
+
+/// Calls the function stored in `hook`, if any.
+fn call_hook(hook: &AtomicPtr<()>) {
+    let hook = hook.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // Safety: hooks are only ever set from `fn()` pointers.
+        unsafe { core::mem::transmute::<*mut (), fn()>(hook)() };
+    }
+}
@@ -0,0 +1,144 @@
+use core::{sync::atomic::AtomicU32, time::Duration};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::AtomicWait;
+
+mod barrier;
+mod condvar;
+mod event;
+mod event_flags;
+mod latch;
+mod lazy;
+mod mutex;
+mod once;
+mod once_lock;
+mod parker;
+mod rwlock;
+mod semaphore;
+mod wait_group;
+
+pub use barrier::{Barrier, BarrierWaitError, BarrierWaitResult};
+#[cfg(feature = "std")]
+pub use condvar::WaitTimeoutResult;
+pub use condvar::{Condvar, CondvarGuard};
+pub use event::{AutoResetEvent, ManualResetEvent};
+pub use event_flags::EventFlags;
+pub use latch::Latch;
+pub use lazy::Lazy;
+pub use mutex::{Mutex, MutexGuard};
+pub use once::{Once, OnceState};
+pub use once_lock::OnceLock;
+#[cfg(feature = "std")]
+pub use parker::{OwnedParker, OwnedUnparker};
+pub use parker::{Parker, Unparker};
+pub use rwlock::{
+    RwLock, RwLockPolicy, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard,
+};
+pub use semaphore::{Semaphore, SemaphorePermit};
+pub use wait_group::WaitGroup;
+
+/// The time by which a blocking operation must finish, if any.
+#[derive(Copy, Clone)]
+struct Deadline {
+    /// The deadline, or `None` to block indefinitely.
+    #[cfg(feature = "std")]
+    at: Option<Instant>,
+}
+
+impl Deadline {
+    /// A deadline that never passes.
+    const NEVER: Self = Self {
+        #[cfg(feature = "std")]
+        at: None,
+    };
+
+    /// A deadline at `instant`.
+    #[cfg(feature = "std")]
+    fn at(instant: Instant) -> Self {
+        Self { at: Some(instant) }
+    }
+
+    /// A deadline once `timeout` has elapsed, or [`Deadline::NEVER`] if that is not representable.
+    #[cfg(feature = "std")]
+    fn after(timeout: Duration) -> Self {
+        Self {
+            at: Instant::now().checked_add(timeout),
+        }
+    }
+
+    /// Gets the time left until the deadline, which is `Some(None)` if there is
+    /// no deadline, or `None` if the deadline has passed.
+    fn remaining(self) -> Option<Option<Duration>> {
+        #[cfg(feature = "std")]
+        if let Some(at) = self.at {
+            let now = Instant::now();
+            return (now < at).then(|| Some(at - now));
+        }
+
+        Some(None)
+    }
+}
+
+/// If `atomic` holds `value`, sleeps until notified or until `deadline`.
+/// Returns `false` if the deadline had already passed.
+fn wait(atomic: &AtomicU32, value: u32, deadline: Deadline) -> bool {
+    match deadline.remaining() {
+        Some(Some(timeout)) => atomic.wait_timeout(value, timeout),
+        Some(None) => atomic.wait(value),
+        None => return false,
+    }
+
+    true
+}
+
+/// If `atomic` holds `value`, sleeps until notified with a mask that
+/// intersects `mask`, or until `deadline`. Returns `false` if the deadline
+/// had already passed.
+///
+/// Bitset waits are only selective on Linux; elsewhere, every notify
+/// wakes the waiter.
+fn wait_bitset(atomic: &AtomicU32, value: u32, mask: u32, deadline: Deadline) -> bool {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if crate::backend::uses_platform() {
+        let Some(timeout) = deadline.remaining() else {
+            return false;
+        };
+
+        crate::platform::futex_wait_bitset(atomic, value, mask, timeout);
+        return true;
+    }
+
+    let _ = mask;
+    wait(atomic, value, deadline)
+}
+
+/// Wakes up to `count` threads waiting on `atomic` with masks that intersect `mask`.
+/// Returns `false` if it is certain that no thread was woken.
+///
+/// Where bitset waits are not selective, every waiter is woken.
+fn notify_bitset(atomic: &AtomicU32, mask: u32, count: u32) -> bool {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if crate::backend::uses_platform() {
+        let woken = crate::platform::futex_wake_bitset(atomic, mask, count);
+        #[cfg(feature = "eventfd")]
+        crate::eventfd::signal(atomic as *const _ as usize);
+        return woken > 0;
+    }
+
+    let _ = (mask, count);
+    atomic.notify_all();
+    true
+}
+
+/// Wakes up to `count` threads waiting on `atomic`.
+/// Where only one or all waiters can be woken, every waiter is woken.
+fn notify_many(atomic: &AtomicU32, count: u32) {
+    match count {
+        0 => {}
+        1 => atomic.notify_one(),
+        _ => {
+            notify_bitset(atomic, u32::MAX, count);
+        }
+    }
+}
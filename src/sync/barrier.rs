@@ -0,0 +1,251 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use super::{Deadline, wait};
+use crate::AtomicWait;
+
+/// The bits of the state that count the parties.
+const PARTIES: u64 = (1 << 24) - 1;
+
+/// A single arrived party in the state.
+const ARRIVED_ONE: u64 = 1 << 24;
+
+/// The bits of the state that count the parties which have arrived in the current phase.
+const ARRIVED: u64 = PARTIES << 24;
+
+/// A single phase in the state.
+const GENERATION_ONE: u64 = 1 << 48;
+
+/// The bits of the state that count the phases that have completed.
+const GENERATION: u64 = 0x7FFF << 48;
+
+/// Set in the state once the barrier is broken.
+const BROKEN: u64 = 1 << 63;
+
+/// A reusable barrier built on [`AtomicWait`], which blocks
+/// threads until a number of parties have all reached it.
+///
+/// The number of parties, the number that have arrived, and the phase are packed into an
+/// [`AtomicU64`], while waiters sleep on an [`AtomicU32`] generation counter, which is
+/// incremented and notified whenever a phase completes or the barrier breaks.
+///
+/// Parties may join or leave between and during phases with [`Barrier::register`]
+/// and [`Barrier::deregister`], so that the remaining threads are not stranded
+/// when one of them stops. A thread that times out in [`Barrier::wait_timeout`]
+/// breaks the barrier, which releases every waiter with an error, and makes
+/// every later wait fail.
+pub struct Barrier {
+    /// The phase, the number of parties, and how many have arrived.
+    state: AtomicU64,
+    /// Incremented whenever a phase completes or the barrier breaks.
+    generation: AtomicU32,
+}
+
+impl Barrier {
+    /// Creates a new barrier that releases waiters once `parties` threads have called
+    /// [`Barrier::wait`]. A barrier with zero or one parties never blocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parties` is greater than 2<sup>24</sup> - 1.
+    pub const fn new(parties: u32) -> Self {
+        assert!(parties as u64 <= PARTIES, "too many barrier parties");
+        Self {
+            state: AtomicU64::new(parties as u64),
+            generation: AtomicU32::new(0),
+        }
+    }
+
+    /// Gets the number of parties that the barrier waits for.
+    pub fn parties(&self) -> u32 {
+        (self.state.load(Ordering::Relaxed) & PARTIES) as u32
+    }
+
+    /// Gets whether the barrier has been broken by a timed out waiter.
+    pub fn is_broken(&self) -> bool {
+        self.state.load(Ordering::Relaxed) & BROKEN != 0
+    }
+
+    /// Blocks until every party has reached the barrier. Exactly
+    /// one thread of each phase, the last to arrive, is the leader.
+    pub fn wait(&self) -> Result<BarrierWaitResult, BarrierWaitError> {
+        self.wait_with(Deadline::NEVER)
+    }
+
+    /// Blocks until every party has reached the barrier, or for at most `timeout`.
+    /// If the timeout elapses first, this thread breaks the barrier.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<BarrierWaitResult, BarrierWaitError> {
+        self.wait_with(Deadline::after(timeout))
+    }
+
+    /// Blocks until every party has reached the barrier, or until `deadline` at
+    /// the latest. If the deadline passes first, this thread breaks the barrier.
+    #[cfg(feature = "std")]
+    pub fn wait_until(&self, deadline: Instant) -> Result<BarrierWaitResult, BarrierWaitError> {
+        self.wait_with(Deadline::at(deadline))
+    }
+
+    /// Adds a party to the barrier, which the current phase also waits for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the barrier would have more than 2<sup>24</sup> - 1 parties.
+    pub fn register(&self) {
+        self.state
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+                (x & PARTIES != PARTIES).then(|| x + 1)
+            })
+            .expect("too many barrier parties");
+    }
+
+    /// Removes a party from the barrier. If every remaining party has already arrived,
+    /// this completes the phase and releases them, without any of them being the
+    /// leader. Returns whether it released them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the barrier has no parties.
+    pub fn deregister(&self) -> bool {
+        let mut state = self.state.load(Ordering::Relaxed);
+        loop {
+            assert!(
+                state & PARTIES != 0,
+                "deregistered from a barrier without parties"
+            );
+            let arrived = (state & ARRIVED) >> 24;
+            let releases = state & BROKEN == 0 && arrived != 0 && arrived >= (state & PARTIES) - 1;
+            let new = if releases {
+                Self::next_phase(state) - 1
+            } else {
+                state - 1
+            };
+
+            match self
+                .state
+                .compare_exchange_weak(state, new, Ordering::AcqRel, Ordering::Relaxed)
+            {
+                Ok(_) => {
+                    if releases {
+                        self.release();
+                    }
+
+                    return releases;
+                }
+                Err(x) => state = x,
+            }
+        }
+    }
+
+    /// Arrives at the barrier and blocks until the phase completes, or until `deadline`.
+    fn wait_with(&self, deadline: Deadline) -> Result<BarrierWaitResult, BarrierWaitError> {
+        let mut state = self.state.load(Ordering::Relaxed);
+        loop {
+            if state & BROKEN != 0 {
+                return Err(BarrierWaitError::Broken);
+            }
+
+            let leader = (state & ARRIVED) >> 24 >= (state & PARTIES).saturating_sub(1);
+            let new = if leader {
+                Self::next_phase(state)
+            } else {
+                state + ARRIVED_ONE
+            };
+
+            match self
+                .state
+                .compare_exchange_weak(state, new, Ordering::AcqRel, Ordering::Relaxed)
+            {
+                Ok(_) if leader => {
+                    self.release();
+                    return Ok(BarrierWaitResult(true));
+                }
+                Ok(_) => break,
+                Err(x) => state = x,
+            }
+        }
+
+        let phase = state & GENERATION;
+        loop {
+            // Load the generation first, so that a phase completing
+            // after the state is checked prevents sleeping.
+            let generation = self.generation.load(Ordering::Acquire);
+            let state = self.state.load(Ordering::Acquire);
+            if state & GENERATION != phase {
+                return Ok(BarrierWaitResult(false));
+            } else if state & BROKEN != 0 {
+                return Err(BarrierWaitError::Broken);
+            }
+
+            if !wait(&self.generation, generation, deadline) {
+                return match self
+                    .state
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |x| {
+                        (x & (GENERATION | BROKEN) == phase).then_some(x | BROKEN)
+                    }) {
+                    Ok(_) => {
+                        self.release();
+                        Err(BarrierWaitError::TimedOut)
+                    }
+                    Err(x) if x & GENERATION != phase => Ok(BarrierWaitResult(false)),
+                    Err(_) => Err(BarrierWaitError::Broken),
+                };
+            }
+        }
+    }
+
+    /// Gets the state at the start of the phase after the one in `state`.
+    fn next_phase(state: u64) -> u64 {
+        (state & GENERATION).wrapping_add(GENERATION_ONE) & GENERATION | state & PARTIES
+    }
+
+    /// Wakes every waiter, after the phase completed or the barrier broke.
+    fn release(&self) {
+        self.generation.fetch_add(1, Ordering::Release);
+        self.generation.notify_all();
+    }
+}
+
+impl fmt::Debug for Barrier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Barrier")
+            .field("parties", &self.parties())
+            .field("broken", &self.is_broken())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returned by [`Barrier::wait`] once every party has reached the barrier.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BarrierWaitResult(bool);
+
+impl BarrierWaitResult {
+    /// Gets whether this thread was the last to arrive, of which there is one in each phase.
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
+
+/// The reason that a wait on a [`Barrier`] failed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BarrierWaitError {
+    /// Another thread broke the barrier, before or during the wait.
+    Broken,
+    /// The wait timed out, and this thread broke the barrier.
+    TimedOut,
+}
+
+impl fmt::Display for BarrierWaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Broken => "the barrier was broken by another thread",
+            Self::TimedOut => "the wait timed out, breaking the barrier",
+        })
+    }
+}
+
+impl core::error::Error for BarrierWaitError {}
@@ -0,0 +1,239 @@
+use core::{
+    fmt,
+    ops::DerefMut,
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::AtomicWait;
+
+/// A condition variable built on [`AtomicWait`].
+///
+/// Waiters sleep on an [`AtomicU32`] sequence counter, which every notify increments,
+/// so a notify that happens after a waiter releases its lock cannot be missed.
+///
+/// A condition variable may be used with the guards of [`Mutex`](super::Mutex), and with
+/// those of any `lock_api` mutex when the `lock_api` feature is enabled. On Linux,
+/// [`Condvar::notify_all`] wakes a single waiter and moves the others onto the futex
+/// of a [`Mutex`](super::Mutex) with `FUTEX_CMP_REQUEUE`, so that they are woken one
+/// at a time as the lock is released, rather than all contending for it at once.
+///
+/// # Panics
+///
+/// Threads that wait at the same time must use the same mutex, so waiting with a
+/// different mutex than the current waiters panics. Once every waiter has returned,
+/// another mutex may be used, which allows both to be moved between waits.
+pub struct Condvar {
+    /// The number of notifies so far.
+    seq: AtomicU32,
+    /// Identifies the mutex that waiters use, or is zero if there are none.
+    lock: AtomicUsize,
+    /// The number of threads that are waiting.
+    waiters: AtomicUsize,
+}
+
+impl Condvar {
+    /// Creates a new condition variable.
+    pub const fn new() -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+            lock: AtomicUsize::new(0),
+            waiters: AtomicUsize::new(0),
+        }
+    }
+
+    /// Releases the lock of `guard` and blocks until this condition variable is notified,
+    /// and then reacquires the lock. This may also return spuriously, without a notify.
+    pub fn wait<G: CondvarGuard>(&self, mut guard: G) -> G {
+        let seq = self.prepare(&guard);
+        guard.unlocked(|| self.seq.wait(seq));
+        self.finish();
+        guard
+    }
+
+    /// Waits like [`Condvar::wait`] for as long as `condition` returns `true`.
+    pub fn wait_while<G, T>(&self, mut guard: G, mut condition: impl FnMut(&mut T) -> bool) -> G
+    where
+        G: CondvarGuard + DerefMut<Target = T>,
+        T: ?Sized,
+    {
+        while condition(&mut *guard) {
+            guard = self.wait(guard);
+        }
+
+        guard
+    }
+
+    /// Waits like [`Condvar::wait_until`], but blocks for at most `timeout`.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout<G: CondvarGuard>(
+        &self,
+        guard: G,
+        timeout: Duration,
+    ) -> (G, WaitTimeoutResult) {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_until(guard, deadline),
+            None => (self.wait(guard), WaitTimeoutResult(false)),
+        }
+    }
+
+    /// Waits like [`Condvar::wait`], but blocks until `deadline` at the latest.
+    /// Unlike [`Condvar::wait`], this does not return spuriously before the deadline.
+    #[cfg(feature = "std")]
+    pub fn wait_until<G: CondvarGuard>(
+        &self,
+        mut guard: G,
+        deadline: Instant,
+    ) -> (G, WaitTimeoutResult) {
+        let seq = self.prepare(&guard);
+
+        // Backends may return early, so the wait only ends
+        // once there has been a notify or the deadline passes.
+        let timed_out = guard.unlocked(|| {
+            loop {
+                if self.seq.load(Ordering::Relaxed) != seq {
+                    return false;
+                }
+
+                let now = Instant::now();
+                if deadline <= now {
+                    return true;
+                }
+
+                self.seq.wait_timeout(seq, deadline - now);
+            }
+        });
+        self.finish();
+
+        (guard, WaitTimeoutResult(timed_out))
+    }
+
+    /// Wakes one thread that is waiting on this condition variable.
+    pub fn notify_one(&self) {
+        self.seq.fetch_add(1, Ordering::Relaxed);
+        self.seq.notify_one();
+    }
+
+    /// Wakes every thread that is waiting on this condition variable.
+    pub fn notify_all(&self) {
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+
+        // Waking one waiter and requeueing the rest is safe even if the mutex is unlocked,
+        // since the woken waiter marks it as contended, and so wakes the next one when it
+        // releases it.
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            let lock = self.lock.load(Ordering::Relaxed);
+            if lock != 0
+                && lock & 1 == 0
+                && crate::backend::uses_platform()
+                && crate::platform::futex_cmp_requeue(
+                    &self.seq,
+                    seq,
+                    1,
+                    i32::MAX as u32,
+                    lock as *const AtomicU32,
+                )
+            {
+                #[cfg(feature = "eventfd")]
+                crate::eventfd::signal(&self.seq as *const _ as usize);
+                return;
+            }
+        }
+
+        let _ = seq;
+        self.seq.notify_all();
+    }
+
+    /// Records the mutex of `guard` as the one that waiters use,
+    /// and returns the sequence number to wait on.
+    fn prepare(&self, guard: &impl CondvarGuard) -> u32 {
+        let id = guard.lock_id();
+        if let Err(lock) = self
+            .lock
+            .compare_exchange(0, id, Ordering::Relaxed, Ordering::Relaxed)
+        {
+            assert_eq!(
+                lock, id,
+                "attempted to use a condition variable with more than one mutex"
+            );
+        }
+
+        self.waiters.fetch_add(1, Ordering::Relaxed);
+        self.seq.load(Ordering::Relaxed)
+    }
+
+    /// Records that a waiter has reacquired its mutex, forgetting the mutex once there
+    /// are no waiters, so that a moved mutex is neither rejected nor requeued onto.
+    ///
+    /// Waiters prepare and finish while holding the mutex, so a new waiter cannot
+    /// record it between the last waiter leaving and the record being cleared.
+    fn finish(&self) {
+        if self.waiters.fetch_sub(1, Ordering::Relaxed) == 1 {
+            self.lock.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Condvar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Condvar").finish_non_exhaustive()
+    }
+}
+
+/// Whether a timed wait on a [`Condvar`] returned because its timeout elapsed.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WaitTimeoutResult(bool);
+
+#[cfg(feature = "std")]
+impl WaitTimeoutResult {
+    /// Gets whether the wait timed out.
+    pub fn timed_out(&self) -> bool {
+        self.0
+    }
+}
+
+/// A mutex guard that a [`Condvar`] can release while it waits.
+///
+/// This is implemented for the guards of [`Mutex`](super::Mutex), and for
+/// those of `lock_api::Mutex` when the `lock_api` feature is enabled.
+pub trait CondvarGuard: private::CondvarGuardImpl {}
+
+#[cfg(feature = "lock_api")]
+impl<R: lock_api::RawMutex, T: ?Sized> private::CondvarGuardImpl
+    for lock_api::MutexGuard<'_, R, T>
+{
+    fn lock_id(&self) -> usize {
+        lock_api::MutexGuard::mutex(self) as *const _ as *const () as usize | 1
+    }
+
+    fn unlocked<U>(&mut self, f: impl FnOnce() -> U) -> U {
+        lock_api::MutexGuard::unlocked(self, f)
+    }
+}
+
+#[cfg(feature = "lock_api")]
+impl<R: lock_api::RawMutex, T: ?Sized> CondvarGuard for lock_api::MutexGuard<'_, R, T> {}
+
+pub(super) mod private {
+    /// A trait that cannot be implemented by other crates.
+    pub trait CondvarGuardImpl {
+        /// Identifies the mutex. This is the address of its [`AtomicU32`](core::sync::atomic::AtomicU32)
+        /// if waiters may be requeued onto it, or otherwise any address with the lowest bit set.
+        ///
+        /// Mutexes that waiters are requeued onto must wait with [`AtomicWait`](crate::AtomicWait),
+        /// and must mark themselves as contended when a waiter reacquires them.
+        fn lock_id(&self) -> usize;
+
+        /// Releases the lock while `f` runs, and then reacquires it.
+        fn unlocked<U>(&mut self, f: impl FnOnce() -> U) -> U;
+    }
+}
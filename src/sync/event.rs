@@ -0,0 +1,252 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use super::{Deadline, wait};
+use crate::AtomicWait;
+
+/// Set in the state of a manual-reset event while it is signalled.
+const SET: u32 = 1;
+
+/// Set in the state of a manual-reset event that is not signalled, while threads may be waiting on it.
+const WAITING: u32 = 2;
+
+/// One increment of the generation of a manual-reset event, which every set advances,
+/// so that waiters are released even if the event is reset before they run.
+const GENERATION: u32 = 4;
+
+/// Set in the state of an auto-reset event while it is signalled.
+const SIGNALLED: u32 = 1;
+
+/// A single waiter in the state of an auto-reset event.
+const WAITER: u32 = 2;
+
+/// An event which, once set, releases every waiter until it is reset, like the manual-reset
+/// events of Windows.
+///
+/// The event is a single [`AtomicU32`], and setting it only notifies if a thread may be waiting.
+/// Every thread that is waiting when the event is set returns, even if it is reset right away.
+pub struct ManualResetEvent {
+    /// Whether the event is set, whether threads may be waiting, and the number of sets.
+    state: AtomicU32,
+}
+
+impl ManualResetEvent {
+    /// Creates a new event, which is initially set if `set` is `true`.
+    pub const fn new(set: bool) -> Self {
+        Self {
+            state: AtomicU32::new(if set { SET } else { 0 }),
+        }
+    }
+
+    /// Gets whether the event is set.
+    pub fn is_set(&self) -> bool {
+        self.state.load(Ordering::Acquire) & SET != 0
+    }
+
+    /// Sets the event, waking every waiting thread.
+    pub fn set(&self) {
+        let state = self
+            .state
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |x| {
+                (x & SET == 0).then(|| (x & !WAITING).wrapping_add(GENERATION) | SET)
+            });
+
+        if state.is_ok_and(|x| x & WAITING != 0) {
+            self.state.notify_all();
+        }
+    }
+
+    /// Resets the event, so that later waits block until it is set again.
+    pub fn reset(&self) {
+        self.state.fetch_and(!SET, Ordering::Relaxed);
+    }
+
+    /// Blocks until the event is set.
+    pub fn wait(&self) {
+        self.wait_with(Deadline::NEVER);
+    }
+
+    /// Blocks until the event is set, or for at most `timeout`.
+    /// Returns whether the event was set.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.wait_with(Deadline::after(timeout))
+    }
+
+    /// Blocks until the event is set, or until `deadline` at the latest.
+    /// Returns whether the event was set.
+    #[cfg(feature = "std")]
+    pub fn wait_until(&self, deadline: Instant) -> bool {
+        self.wait_with(Deadline::at(deadline))
+    }
+
+    /// Blocks until the event is set, or until `deadline`. Returns whether it was set.
+    fn wait_with(&self, deadline: Deadline) -> bool {
+        let mut state = self.state.load(Ordering::Acquire);
+        let generation = state & !(SET | WAITING);
+        loop {
+            // A set since the wait began releases it, even if the event was reset since.
+            if state & SET != 0 || state & !(SET | WAITING) != generation {
+                return true;
+            }
+
+            if state & WAITING == 0 {
+                if let Err(x) = self.state.compare_exchange_weak(
+                    state,
+                    state | WAITING,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    state = x;
+                    continue;
+                }
+
+                state |= WAITING;
+            }
+
+            let timed_out = !wait(&self.state, state, deadline);
+            state = self.state.load(Ordering::Acquire);
+            if timed_out {
+                return state & SET != 0 || state & !(SET | WAITING) != generation;
+            }
+        }
+    }
+}
+
+impl Default for ManualResetEvent {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl fmt::Debug for ManualResetEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ManualResetEvent")
+            .field("set", &self.is_set())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An event which, once set, releases a single waiter and then resets itself, like
+/// the auto-reset events of Windows. If no thread is waiting, the event stays set
+/// until one waits. Setting an event that is already set has no effect.
+///
+/// The event is a single [`AtomicU32`], which holds the signal along with
+/// a count of the waiters, so that setting it wakes exactly one of them.
+pub struct AutoResetEvent {
+    /// Whether the event is set, and the number of waiting threads.
+    state: AtomicU32,
+}
+
+impl AutoResetEvent {
+    /// Creates a new event, which is initially set if `set` is `true`.
+    pub const fn new(set: bool) -> Self {
+        Self {
+            state: AtomicU32::new(if set { SIGNALLED } else { 0 }),
+        }
+    }
+
+    /// Gets whether the event is set.
+    pub fn is_set(&self) -> bool {
+        self.state.load(Ordering::Acquire) & SIGNALLED != 0
+    }
+
+    /// Sets the event, waking one waiting thread if there are any.
+    pub fn set(&self) {
+        let state = self.state.fetch_or(SIGNALLED, Ordering::Release);
+        if state & SIGNALLED == 0 && state >= WAITER {
+            self.state.notify_one();
+        }
+    }
+
+    /// Resets the event, if no thread has consumed the signal yet.
+    pub fn reset(&self) {
+        self.state.fetch_and(!SIGNALLED, Ordering::Relaxed);
+    }
+
+    /// Blocks until the event is set, and then resets it.
+    pub fn wait(&self) {
+        if !self.try_consume() {
+            self.wait_contended(Deadline::NEVER);
+        }
+    }
+
+    /// Resets the event if it is set, without blocking. Returns whether it was set.
+    pub fn try_wait(&self) -> bool {
+        self.try_consume()
+    }
+
+    /// Blocks until the event is set, or for at most `timeout`,
+    /// and then resets it. Returns whether the event was set.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.try_consume() || self.wait_contended(Deadline::after(timeout))
+    }
+
+    /// Blocks until the event is set, or until `deadline` at the latest,
+    /// and then resets it. Returns whether the event was set.
+    #[cfg(feature = "std")]
+    pub fn wait_until(&self, deadline: Instant) -> bool {
+        self.try_consume() || self.wait_contended(Deadline::at(deadline))
+    }
+
+    /// Clears the signal if the event is set. Returns whether it was set.
+    fn try_consume(&self) -> bool {
+        self.state.fetch_and(!SIGNALLED, Ordering::Acquire) & SIGNALLED != 0
+    }
+
+    /// Blocks until the signal is consumed, or until `deadline`. Returns whether it was consumed.
+    #[cold]
+    fn wait_contended(&self, deadline: Deadline) -> bool {
+        let mut state = self.state.fetch_add(WAITER, Ordering::Relaxed) + WAITER;
+        loop {
+            if state & SIGNALLED != 0 {
+                match self.state.compare_exchange_weak(
+                    state,
+                    state - SIGNALLED - WAITER,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return true,
+                    Err(x) => {
+                        state = x;
+                        continue;
+                    }
+                }
+            }
+
+            if !wait(&self.state, state, deadline) {
+                break;
+            }
+
+            state = self.state.load(Ordering::Relaxed);
+        }
+
+        // This thread may have been woken to consume the signal just before timing
+        // out, in which case another waiter must be woken in its place.
+        let state = self.state.fetch_sub(WAITER, Ordering::Relaxed) - WAITER;
+        if state & SIGNALLED != 0 && state >= WAITER {
+            self.state.notify_one();
+        }
+
+        false
+    }
+}
+
+impl Default for AutoResetEvent {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl fmt::Debug for AutoResetEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutoResetEvent")
+            .field("set", &self.is_set())
+            .finish_non_exhaustive()
+    }
+}
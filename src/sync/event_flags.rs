@@ -0,0 +1,160 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use super::{Deadline, notify_bitset, wait_bitset};
+
+/// A group of 32 event flags built on [`AtomicWait`](crate::AtomicWait), like
+/// the event groups of real-time operating systems, which threads may wait
+/// on until any or all of a mask of the flags are set.
+///
+/// The flags are an [`AtomicU32`], upon which waiters sleep with their mask as the
+/// bitset, so that setting flags only wakes threads waiting on those flags. On
+/// platforms without bitset waits, setting flags wakes every waiter.
+pub struct EventFlags {
+    /// The flags.
+    flags: AtomicU32,
+    /// The number of threads waiting for flags.
+    waiters: AtomicU32,
+}
+
+impl EventFlags {
+    /// Creates a new group with `flags` initially set.
+    pub const fn new(flags: u32) -> Self {
+        Self {
+            flags: AtomicU32::new(flags),
+            waiters: AtomicU32::new(0),
+        }
+    }
+
+    /// Gets the flags that are set.
+    pub fn get(&self) -> u32 {
+        self.flags.load(Ordering::Acquire)
+    }
+
+    /// Sets the flags in `bits`, waking the threads waiting on any of them.
+    /// Returns the flags that were set before.
+    pub fn set(&self, bits: u32) -> u32 {
+        let flags = self.flags.fetch_or(bits, Ordering::SeqCst);
+        let new = bits & !flags;
+        if new != 0 && self.waiters.load(Ordering::SeqCst) != 0 {
+            notify_bitset(&self.flags, new, i32::MAX as u32);
+        }
+
+        flags
+    }
+
+    /// Clears the flags in `bits`. Returns the flags that were set before.
+    pub fn clear(&self, bits: u32) -> u32 {
+        self.flags.fetch_and(!bits, Ordering::Relaxed)
+    }
+
+    /// Blocks until any of the flags in `mask` are set, and returns the flags at that
+    /// point. If `clear` is `true`, the flags in `mask` that were set are cleared as
+    /// the wait completes. This blocks forever if `mask` is zero.
+    pub fn wait_any(&self, mask: u32, clear: bool) -> u32 {
+        self.wait_with(mask, false, clear, Deadline::NEVER).unwrap()
+    }
+
+    /// Blocks until any of the flags in `mask` are set, or for at most `timeout`.
+    /// Returns the flags once any in `mask` were set, or `None` on timeout.
+    #[cfg(feature = "std")]
+    pub fn wait_any_timeout(&self, mask: u32, clear: bool, timeout: Duration) -> Option<u32> {
+        self.wait_with(mask, false, clear, Deadline::after(timeout))
+    }
+
+    /// Blocks until any of the flags in `mask` are set, or until `deadline` at the latest.
+    /// Returns the flags once any in `mask` were set, or `None` on timeout.
+    #[cfg(feature = "std")]
+    pub fn wait_any_until(&self, mask: u32, clear: bool, deadline: Instant) -> Option<u32> {
+        self.wait_with(mask, false, clear, Deadline::at(deadline))
+    }
+
+    /// Blocks until all the flags in `mask` are set, and returns the flags at that
+    /// point. If `clear` is `true`, the flags in `mask` are cleared as the wait
+    /// completes. This does not block if `mask` is zero.
+    pub fn wait_all(&self, mask: u32, clear: bool) -> u32 {
+        self.wait_with(mask, true, clear, Deadline::NEVER).unwrap()
+    }
+
+    /// Blocks until all the flags in `mask` are set, or for at most `timeout`.
+    /// Returns the flags once all in `mask` were set, or `None` on timeout.
+    #[cfg(feature = "std")]
+    pub fn wait_all_timeout(&self, mask: u32, clear: bool, timeout: Duration) -> Option<u32> {
+        self.wait_with(mask, true, clear, Deadline::after(timeout))
+    }
+
+    /// Blocks until all the flags in `mask` are set, or until `deadline` at the latest.
+    /// Returns the flags once all in `mask` were set, or `None` on timeout.
+    #[cfg(feature = "std")]
+    pub fn wait_all_until(&self, mask: u32, clear: bool, deadline: Instant) -> Option<u32> {
+        self.wait_with(mask, true, clear, Deadline::at(deadline))
+    }
+
+    /// Takes the flags in `mask` if any or `all` of them are set, clearing them if `clear`
+    /// is `true`. Returns the flags before they were cleared, if the wait completed.
+    fn try_take(&self, mask: u32, all: bool, clear: bool) -> Option<u32> {
+        let ready = |flags: u32| {
+            if all {
+                flags & mask == mask
+            } else {
+                flags & mask != 0
+            }
+        };
+
+        if !clear {
+            let flags = self.flags.load(Ordering::Acquire);
+            return ready(flags).then_some(flags);
+        }
+
+        self.flags
+            .fetch_update(Ordering::Acquire, Ordering::Acquire, |x| {
+                ready(x).then_some(x & !mask)
+            })
+            .ok()
+    }
+
+    /// Blocks until any or `all` of the flags in `mask` are set, or until `deadline`.
+    fn wait_with(&self, mask: u32, all: bool, clear: bool, deadline: Deadline) -> Option<u32> {
+        if let Some(flags) = self.try_take(mask, all, clear) {
+            return Some(flags);
+        }
+
+        // Count this thread as a waiter before checking the flags
+        // again, so that threads setting flags are sure to notice it.
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+
+        // A zero mask would be rejected by bitset waits, and is never satisfied anyway.
+        let bitset = if mask == 0 { u32::MAX } else { mask };
+        let flags = loop {
+            let flags = self.flags.load(Ordering::SeqCst);
+            if let Some(flags) = self.try_take(mask, all, clear) {
+                break Some(flags);
+            }
+
+            if !wait_bitset(&self.flags, flags, bitset, deadline) {
+                break self.try_take(mask, all, clear);
+            }
+        };
+
+        self.waiters.fetch_sub(1, Ordering::Relaxed);
+        flags
+    }
+}
+
+impl Default for EventFlags {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl fmt::Debug for EventFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventFlags")
+            .field("flags", &format_args!("{:#010x}", self.get()))
+            .finish_non_exhaustive()
+    }
+}
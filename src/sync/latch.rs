@@ -0,0 +1,92 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use super::{Deadline, wait};
+use crate::AtomicWait;
+
+/// A single-use latch built on [`AtomicWait`], which blocks threads until it has been
+/// counted down to zero, and then releases every waiter permanently.
+///
+/// The count is an [`AtomicU32`], upon which waiters sleep,
+/// and which is notified once it reaches zero.
+pub struct Latch {
+    /// The number of times the latch must still be counted down.
+    count: AtomicU32,
+}
+
+impl Latch {
+    /// Creates a new latch that releases waiters after [`Latch::count_down`] is
+    /// called `count` times. A latch with a count of zero never blocks.
+    pub const fn new(count: u32) -> Self {
+        Self {
+            count: AtomicU32::new(count),
+        }
+    }
+
+    /// Gets the number of times the latch must still be counted down.
+    pub fn count(&self) -> u32 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Counts the latch down once, releasing every waiter if this brings it to
+    /// zero. Counting down a latch that has already reached zero has no effect.
+    pub fn count_down(&self) {
+        if self
+            .count
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |x| x.checked_sub(1))
+            == Ok(1)
+        {
+            self.count.notify_all();
+        }
+    }
+
+    /// Gets whether the latch has reached zero, without blocking.
+    pub fn try_wait(&self) -> bool {
+        self.count.load(Ordering::Acquire) == 0
+    }
+
+    /// Blocks until the latch reaches zero.
+    pub fn wait(&self) {
+        self.wait_with(Deadline::NEVER);
+    }
+
+    /// Blocks until the latch reaches zero, or for at most `timeout`.
+    /// Returns whether it reached zero.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.wait_with(Deadline::after(timeout))
+    }
+
+    /// Blocks until the latch reaches zero, or until `deadline` at the latest.
+    /// Returns whether it reached zero.
+    #[cfg(feature = "std")]
+    pub fn wait_until(&self, deadline: Instant) -> bool {
+        self.wait_with(Deadline::at(deadline))
+    }
+
+    /// Blocks until the latch reaches zero, or until `deadline`. Returns whether it did.
+    fn wait_with(&self, deadline: Deadline) -> bool {
+        loop {
+            let count = self.count.load(Ordering::Acquire);
+            if count == 0 {
+                return true;
+            }
+
+            if !wait(&self.count, count, deadline) {
+                return false;
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Latch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Latch")
+            .field("count", &self.count())
+            .finish_non_exhaustive()
+    }
+}
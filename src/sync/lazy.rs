@@ -0,0 +1,76 @@
+use core::{
+    cell::Cell,
+    fmt,
+    ops::Deref,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use super::OnceLock;
+
+/// A value built on [`OnceLock`], which is initialized on its first
+/// access, like the `LazyLock` of `std`.
+///
+/// Threads that access the value while another initializes it block until it finishes.
+/// If the initializer panics, the value is poisoned, and every later access panics.
+pub struct Lazy<T, F = fn() -> T> {
+    /// The value, once initialized.
+    cell: OnceLock<T>,
+    /// The initializer, which is taken by the thread that runs it.
+    init: Cell<Option<F>>,
+}
+
+// SAFETY: the initializer is only accessed by the thread that runs it, under the `Once`.
+unsafe impl<T, F: Send> Sync for Lazy<T, F> where OnceLock<T>: Sync {}
+impl<T, F: UnwindSafe> RefUnwindSafe for Lazy<T, F> where OnceLock<T>: RefUnwindSafe {}
+
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    /// Creates a new value, which is initialized with `f` on its first access.
+    pub const fn new(f: F) -> Self {
+        Self {
+            cell: OnceLock::new(),
+            init: Cell::new(Some(f)),
+        }
+    }
+
+    /// Initializes the value if it has not been already, and then gets it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the initializer panicked.
+    pub fn force(this: &Self) -> &T {
+        this.cell.get_or_init(|| match this.init.take() {
+            Some(f) => f(),
+            None => panic!("Lazy instance has previously been poisoned"),
+        })
+    }
+
+    /// Gets the value, or `None` if it has not been initialized.
+    pub fn get(this: &Self) -> Option<&T> {
+        this.cell.get()
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Self::force(self)
+    }
+}
+
+impl<T: Default> Default for Lazy<T> {
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("Lazy");
+        match self.cell.get() {
+            Some(value) => d.field(value),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
@@ -0,0 +1,289 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    hint::spin_loop,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::{
+    sync::{LockResult, PoisonError, atomic::AtomicBool},
+    time::{Duration, Instant},
+};
+
+use super::condvar::{CondvarGuard, private::CondvarGuardImpl};
+use crate::AtomicWait;
+
+/// The state of an unlocked mutex.
+const UNLOCKED: u32 = 0;
+
+/// The state of a locked mutex that no threads are waiting for.
+const LOCKED: u32 = 1;
+
+/// The state of a locked mutex that other threads may be waiting for.
+const CONTENDED: u32 = 2;
+
+/// The number of times to check the state before sleeping, while another thread holds the lock.
+const SPIN_LIMIT: u32 = 100;
+
+/// A mutual exclusion lock built on [`AtomicWait`].
+///
+/// The lock is a single [`AtomicU32`] that is unlocked, locked, or locked with
+/// waiters, so locking and unlocking without contention are a single atomic
+/// operation, and unlocking only notifies when another thread may be waiting.
+///
+/// With the `std` feature, the mutex is poisoned if a thread panics while holding
+/// it. Poisoning is only reported by [`Mutex::lock_checked`] and [`Mutex::is_poisoned`],
+/// so callers that do not care about it can use [`Mutex::lock`] directly.
+pub struct Mutex<T: ?Sized> {
+    /// The lock state.
+    state: AtomicU32,
+    /// Whether a thread panicked while holding the lock.
+    #[cfg(feature = "std")]
+    poisoned: AtomicBool,
+    /// The protected data.
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    /// Creates a new, unlocked mutex.
+    pub const fn new(value: T) -> Self {
+        Self {
+            state: AtomicU32::new(UNLOCKED),
+            #[cfg(feature = "std")]
+            poisoned: AtomicBool::new(false),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the mutex, returning the protected data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Acquires the mutex, blocking until it is available.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        if self
+            .state
+            .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            self.lock_contended();
+        }
+
+        MutexGuard::new(self)
+    }
+
+    /// Attempts to acquire the mutex without blocking.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.state
+            .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+            .then(|| MutexGuard::new(self))
+    }
+
+    /// Attempts to acquire the mutex, blocking for at most `timeout`.
+    #[cfg(feature = "std")]
+    pub fn try_lock_for(&self, timeout: Duration) -> Option<MutexGuard<'_, T>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.try_lock_until(deadline),
+            None => Some(self.lock()),
+        }
+    }
+
+    /// Attempts to acquire the mutex, blocking until `deadline` at the latest.
+    #[cfg(feature = "std")]
+    pub fn try_lock_until(&self, deadline: Instant) -> Option<MutexGuard<'_, T>> {
+        if let Some(guard) = self.try_lock() {
+            return Some(guard);
+        }
+
+        self.spin();
+        loop {
+            if self.state.swap(CONTENDED, Ordering::Acquire) == UNLOCKED {
+                return Some(MutexGuard::new(self));
+            }
+
+            let now = Instant::now();
+            if deadline <= now {
+                return None;
+            }
+
+            self.state.wait_timeout(CONTENDED, deadline - now);
+        }
+    }
+
+    /// Acquires the mutex like [`Mutex::lock`], but fails if
+    /// a thread panicked while holding it. The mutex is
+    /// still acquired in that case, and the guard may be
+    /// recovered with [`PoisonError::into_inner`].
+    #[cfg(feature = "std")]
+    pub fn lock_checked(&self) -> LockResult<MutexGuard<'_, T>> {
+        let guard = self.lock();
+        if self.is_poisoned() {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
+    }
+
+    /// Gets whether a thread panicked while holding the mutex.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Relaxed)
+    }
+
+    /// Clears the poisoned state of the mutex.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Relaxed);
+    }
+
+    /// Gets a mutable reference to the protected data.
+    /// No locking is needed, since the mutex is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Spins briefly, and then blocks until the lock is acquired.
+    #[cold]
+    fn lock_contended(&self) {
+        self.spin();
+        self.relock();
+    }
+
+    /// Blocks until the lock is acquired, marking it as contended. This is also how
+    /// [`Condvar`](super::Condvar) waiters reacquire it, since others may have been requeued onto it.
+    fn relock(&self) {
+        while self.state.swap(CONTENDED, Ordering::Acquire) != UNLOCKED {
+            self.state.wait(CONTENDED);
+        }
+    }
+
+    /// Spins for a short while, in case the owner is about to release the lock.
+    fn spin(&self) {
+        for _ in 0..SPIN_LIMIT {
+            if self.state.load(Ordering::Relaxed) != LOCKED {
+                break;
+            }
+
+            spin_loop();
+        }
+    }
+
+    /// Releases the lock, waking a waiter if there may be one.
+    fn unlock(&self) {
+        if self.state.swap(UNLOCKED, Ordering::Release) == CONTENDED {
+            self.state.notify_one();
+        }
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for Mutex<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Mutex");
+        match self.try_lock() {
+            Some(guard) => d.field("data", &&*guard),
+            None => d.field("data", &format_args!("<locked>")),
+        };
+        #[cfg(feature = "std")]
+        d.field("poisoned", &self.is_poisoned());
+        d.finish_non_exhaustive()
+    }
+}
+
+/// Provides access to the data of a locked [`Mutex`], and unlocks it when dropped.
+pub struct MutexGuard<'a, T: ?Sized> {
+    /// The locked mutex.
+    mutex: &'a Mutex<T>,
+    /// Whether the thread was already panicking when it acquired the lock,
+    /// in which case a panic does not poison the mutex.
+    #[cfg(feature = "std")]
+    panicking: bool,
+    /// Prevents the guard from being sent to other threads, like `std`'s guards.
+    marker: PhantomData<*const ()>,
+}
+
+impl<'a, T: ?Sized> MutexGuard<'a, T> {
+    /// Creates a guard for a mutex that the current thread has locked.
+    fn new(mutex: &'a Mutex<T>) -> Self {
+        Self {
+            mutex,
+            #[cfg(feature = "std")]
+            panicking: std::thread::panicking(),
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for MutexGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        if !self.panicking && std::thread::panicking() {
+            self.mutex.poisoned.store(true, Ordering::Relaxed);
+        }
+
+        self.mutex.unlock();
+    }
+}
+
+impl<T: ?Sized> CondvarGuardImpl for MutexGuard<'_, T> {
+    fn lock_id(&self) -> usize {
+        &self.mutex.state as *const _ as usize
+    }
+
+    fn unlocked<U>(&mut self, f: impl FnOnce() -> U) -> U {
+        self.mutex.unlock();
+        let result = f();
+        self.mutex.relock();
+        result
+    }
+}
+
+impl<T: ?Sized> CondvarGuard for MutexGuard<'_, T> {}
@@ -0,0 +1,248 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use super::{Deadline, wait};
+use crate::AtomicWait;
+
+/// The state of a [`Once`] that has not run yet.
+const INCOMPLETE: u32 = 0;
+
+/// The state of a [`Once`] whose initializer panicked.
+const POISONED: u32 = 1;
+
+/// The state of a [`Once`] whose initializer is running.
+const RUNNING: u32 = 2;
+
+/// The state of a [`Once`] that has completed.
+const COMPLETE: u32 = 3;
+
+/// The bits of the state that hold one of the above.
+const STATE_MASK: u32 = 0b11;
+
+/// Set in the state while threads may be waiting for the initializer to finish.
+const QUEUED: u32 = 0b100;
+
+/// A synchronization primitive built on [`AtomicWait`], which runs
+/// an initializer exactly once, like the `Once` of `std`.
+///
+/// The state is an [`AtomicU32`], upon which threads waiting for the initializer sleep,
+/// and which is only notified if a thread may be waiting. If the initializer panics,
+/// the `Once` is poisoned, which makes later calls to [`Once::call_once`] panic,
+/// while [`Once::call_once_force`] may run another initializer. Poisoning relies
+/// on unwinding, and so works without the `std` feature.
+pub struct Once {
+    /// One of the states above, and whether threads may be waiting.
+    state: AtomicU32,
+}
+
+impl Once {
+    /// Creates a new `Once` that has not run yet.
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(INCOMPLETE),
+        }
+    }
+
+    /// Gets whether an initializer has completed.
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    /// Runs `f` if no initializer has completed yet, or blocks until
+    /// the initializer that another thread is running completes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Once` is poisoned, or becomes poisoned while waiting.
+    pub fn call_once(&self, f: impl FnOnce()) {
+        if self.is_completed() {
+            return;
+        }
+
+        let mut f = Some(f);
+        self.call(false, &mut |_| f.take().unwrap()());
+    }
+
+    /// Runs `f` if no initializer has completed yet, even if the `Once` is
+    /// poisoned, or blocks until the initializer that another thread is
+    /// running completes. A completed initializer clears the poison.
+    pub fn call_once_force(&self, f: impl FnOnce(&OnceState)) {
+        if self.is_completed() {
+            return;
+        }
+
+        let mut f = Some(f);
+        self.call(true, &mut |state| f.take().unwrap()(state));
+    }
+
+    /// Blocks until an initializer has completed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Once` is poisoned, or becomes poisoned while waiting.
+    pub fn wait(&self) {
+        self.wait_with(false, Deadline::NEVER);
+    }
+
+    /// Blocks until an initializer has completed, ignoring poisoning.
+    pub fn wait_force(&self) {
+        self.wait_with(true, Deadline::NEVER);
+    }
+
+    /// Blocks until an initializer has completed, or for at most
+    /// `timeout`. Returns whether an initializer completed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Once` is poisoned, or becomes poisoned while waiting.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.wait_with(false, Deadline::after(timeout))
+    }
+
+    /// Blocks until an initializer has completed, or until `deadline`
+    /// at the latest. Returns whether an initializer completed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Once` is poisoned, or becomes poisoned while waiting.
+    #[cfg(feature = "std")]
+    pub fn wait_until(&self, deadline: Instant) -> bool {
+        self.wait_with(false, Deadline::at(deadline))
+    }
+
+    /// Blocks until an initializer has completed, ignoring poisoning if `force` is
+    /// `true`, or until `deadline`. Returns whether an initializer completed.
+    pub(super) fn wait_with(&self, force: bool, deadline: Deadline) -> bool {
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            match state & STATE_MASK {
+                COMPLETE => return true,
+                POISONED if !force => panic!("Once instance has previously been poisoned"),
+                _ => {}
+            }
+
+            if !self.queue(&mut state) {
+                continue;
+            }
+
+            if !wait(&self.state, state, deadline) {
+                return self.is_completed();
+            }
+
+            state = self.state.load(Ordering::Acquire);
+        }
+    }
+
+    /// Runs `f` unless an initializer has completed, ignoring poisoning if `force` is `true`.
+    #[cold]
+    fn call(&self, force: bool, f: &mut dyn FnMut(&OnceState)) {
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            match state & STATE_MASK {
+                COMPLETE => return,
+                POISONED if !force => panic!("Once instance has previously been poisoned"),
+                INCOMPLETE | POISONED => {
+                    if let Err(x) = self.state.compare_exchange_weak(
+                        state,
+                        RUNNING | state & QUEUED,
+                        Ordering::Acquire,
+                        Ordering::Acquire,
+                    ) {
+                        state = x;
+                        continue;
+                    }
+
+                    // Poison the `Once` if `f` unwinds.
+                    let mut guard = CompletionGuard {
+                        state: &self.state,
+                        set_on_drop: POISONED,
+                    };
+
+                    f(&OnceState {
+                        poisoned: state & STATE_MASK == POISONED,
+                    });
+
+                    guard.set_on_drop = COMPLETE;
+                    return;
+                }
+                _ => {
+                    if self.queue(&mut state) {
+                        wait(&self.state, state, Deadline::NEVER);
+                        state = self.state.load(Ordering::Acquire);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Marks `state` as having waiters, so that the initializer notifies them.
+    /// Returns `false`, with `state` updated, if the state changed first.
+    fn queue(&self, state: &mut u32) -> bool {
+        if *state & QUEUED != 0 {
+            return true;
+        }
+
+        match self.state.compare_exchange_weak(
+            *state,
+            *state | QUEUED,
+            Ordering::Acquire,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                *state |= QUEUED;
+                true
+            }
+            Err(x) => {
+                *state = x;
+                false
+            }
+        }
+    }
+}
+
+impl Default for Once {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Once {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Once").finish_non_exhaustive()
+    }
+}
+
+/// Passed to the initializer of [`Once::call_once_force`].
+#[derive(Debug)]
+pub struct OnceState {
+    /// Whether a previous initializer panicked.
+    poisoned: bool,
+}
+
+impl OnceState {
+    /// Gets whether a previous initializer panicked, poisoning the [`Once`].
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+}
+
+/// Publishes the outcome of an initializer, and wakes the threads waiting for it.
+struct CompletionGuard<'a> {
+    /// The state of the [`Once`].
+    state: &'a AtomicU32,
+    /// The state to store, which remains [`POISONED`] unless the initializer returns.
+    set_on_drop: u32,
+}
+
+impl Drop for CompletionGuard<'_> {
+    fn drop(&mut self) {
+        if self.state.swap(self.set_on_drop, Ordering::Release) & QUEUED != 0 {
+            self.state.notify_all();
+        }
+    }
+}
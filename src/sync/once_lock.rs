@@ -0,0 +1,152 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    mem::MaybeUninit,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "std")]
+use super::Deadline;
+use super::Once;
+
+/// A cell built on [`Once`], which is written to at most once, like the `OnceLock` of `std`.
+///
+/// Threads that find another initializing the cell block until it finishes. If the
+/// initializer panics, the cell stays empty, and the next caller initializes it instead.
+pub struct OnceLock<T> {
+    /// Completes once the value is written.
+    once: Once,
+    /// The value, which is initialized once `once` completes.
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+unsafe impl<T: Send> Send for OnceLock<T> {}
+unsafe impl<T: Send + Sync> Sync for OnceLock<T> {}
+impl<T: RefUnwindSafe + UnwindSafe> RefUnwindSafe for OnceLock<T> {}
+impl<T: UnwindSafe> UnwindSafe for OnceLock<T> {}
+
+impl<T> OnceLock<T> {
+    /// Creates a new empty cell.
+    pub const fn new() -> Self {
+        Self {
+            once: Once::new(),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Gets the value, or `None` if the cell is empty or being initialized.
+    pub fn get(&self) -> Option<&T> {
+        // SAFETY: the value was written before the `Once` completed, and is never written again.
+        self.once
+            .is_completed()
+            .then(|| unsafe { (*self.value.get()).assume_init_ref() })
+    }
+
+    /// Gets a mutable reference to the value, or `None` if the cell is empty.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the value was written before the `Once` completed.
+        self.once
+            .is_completed()
+            .then(|| unsafe { self.value.get_mut().assume_init_mut() })
+    }
+
+    /// Initializes the cell with `value` if it is empty, blocking while another thread
+    /// initializes it. Returns `value` back if the cell was already initialized.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Gets the value, initializing the cell with `f` if it is empty, or blocking
+    /// while another thread initializes it. If `f` panics, the cell stays empty.
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+
+        self.once.call_once_force(|_| {
+            // SAFETY: only the thread running the initializer accesses the value.
+            unsafe { (*self.value.get()).write(f()) };
+        });
+
+        self.get().unwrap()
+    }
+
+    /// Blocks until the cell is initialized, and then gets the value.
+    pub fn wait(&self) -> &T {
+        self.once.wait_force();
+        self.get().unwrap()
+    }
+
+    /// Blocks until the cell is initialized, or for at most `timeout`.
+    /// Returns the value, or `None` if the cell is still empty.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<&T> {
+        self.once.wait_with(true, Deadline::after(timeout));
+        self.get()
+    }
+
+    /// Blocks until the cell is initialized, or until `deadline` at the
+    /// latest. Returns the value, or `None` if the cell is still empty.
+    #[cfg(feature = "std")]
+    pub fn wait_until(&self, deadline: Instant) -> Option<&T> {
+        self.once.wait_with(true, Deadline::at(deadline));
+        self.get()
+    }
+
+    /// Takes the value out of the cell, leaving it empty.
+    pub fn take(&mut self) -> Option<T> {
+        if !self.once.is_completed() {
+            return None;
+        }
+
+        self.once = Once::new();
+        // SAFETY: the value was written, and the cell is now empty, so it is not dropped again.
+        Some(unsafe { self.value.get_mut().assume_init_read() })
+    }
+
+    /// Consumes the cell, returning the value if it was initialized.
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
+    }
+}
+
+impl<T> Default for OnceLock<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for OnceLock<T> {
+    fn from(value: T) -> Self {
+        let cell = Self::new();
+        let _ = cell.set(value);
+        cell
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OnceLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("OnceLock");
+        match self.get() {
+            Some(value) => d.field(value),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Drop for OnceLock<T> {
+    fn drop(&mut self) {
+        if self.once.is_completed() {
+            // SAFETY: the value was written, and is never used again.
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use core::{
+    cell::Cell,
+    fmt,
+    marker::PhantomData,
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::{Deadline, wait};
+use crate::AtomicWait;
+
+/// The state of a parker without a token, whose thread is not parked.
+const EMPTY: u32 = 0;
+
+/// The state of a parker that holds a token.
+const NOTIFIED: u32 = 1;
+
+/// The state of a parker whose thread is parked, or about to park.
+const PARKED: u32 = u32::MAX;
+
+/// A thread parker built on [`AtomicWait`], with the token semantics of
+/// `std::thread::park`, which works without `std` and with any backend.
+///
+/// Each parker holds at most one token. [`Unparker::unpark`] makes the token available,
+/// and [`Parker::park`] consumes it, blocking until it is available. An unpark that
+/// happens before the park is not lost, and several unparks before a park only
+/// release a single park. Unlike `std::thread::park`, a park only returns once it
+/// has consumed the token, or once its timeout elapses.
+///
+/// The token is an [`AtomicU32`], upon which the owning thread sleeps, and
+/// unparking only notifies if the thread is parked. A parker may be shared with
+/// other threads through [`Unparker`] handles, which borrow it, but only the
+/// thread that owns it may park. [`OwnedParker`] provides handles that do not
+/// borrow the parker, and so may be sent to threads that outlive it.
+pub struct Parker {
+    /// Whether the parker holds a token, or whether its thread is parked.
+    state: AtomicU32,
+    /// Only one thread may park at a time.
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl Parker {
+    /// Creates a new parker without a token.
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(EMPTY),
+            _not_sync: PhantomData,
+        }
+    }
+
+    /// Gets a handle that other threads can use to unpark this parker.
+    pub fn unparker(&self) -> Unparker<'_> {
+        Unparker { state: &self.state }
+    }
+
+    /// Blocks until the token is available, and then consumes it.
+    pub fn park(&self) {
+        self.park_with(Deadline::NEVER);
+    }
+
+    /// Blocks until the token is available, or for at most `timeout`,
+    /// consuming the token if it was. Returns whether it was consumed.
+    #[cfg(feature = "std")]
+    pub fn park_timeout(&self, timeout: Duration) -> bool {
+        self.park_with(Deadline::after(timeout))
+    }
+
+    /// Blocks until the token is available, or until `deadline` at the latest,
+    /// consuming the token if it was. Returns whether it was consumed.
+    #[cfg(feature = "std")]
+    pub fn park_deadline(&self, deadline: Instant) -> bool {
+        self.park_with(Deadline::at(deadline))
+    }
+
+    /// Blocks until the token is consumed, or until `deadline`. Returns whether it was.
+    fn park_with(&self, deadline: Deadline) -> bool {
+        park(&self.state, deadline)
+    }
+}
+
+impl Default for Parker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Parker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Parker").finish_non_exhaustive()
+    }
+}
+
+/// A handle that unparks a [`Parker`], which may be copied and sent to other threads.
+#[derive(Copy, Clone)]
+pub struct Unparker<'a> {
+    /// The state of the parker.
+    state: &'a AtomicU32,
+}
+
+impl Unparker<'_> {
+    /// Makes the token of the parker available, waking its thread if it is parked.
+    pub fn unpark(&self) {
+        unpark(self.state);
+    }
+}
+
+impl fmt::Debug for Unparker<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unparker").finish_non_exhaustive()
+    }
+}
+
+/// A [`Parker`] whose token is reference-counted, so that its [`OwnedUnparker`]
+/// handles may be sent to any thread, including ones that outlive the parker.
+#[cfg(feature = "std")]
+pub struct OwnedParker {
+    /// Whether the parker holds a token, or whether its thread is parked.
+    state: Arc<AtomicU32>,
+    /// Only one thread may park at a time.
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+#[cfg(feature = "std")]
+impl OwnedParker {
+    /// Creates a new parker without a token.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(AtomicU32::new(EMPTY)),
+            _not_sync: PhantomData,
+        }
+    }
+
+    /// Gets a handle that other threads can use to unpark this parker.
+    pub fn unparker(&self) -> OwnedUnparker {
+        OwnedUnparker {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Blocks until the token is available, and then consumes it.
+    pub fn park(&self) {
+        park(&self.state, Deadline::NEVER);
+    }
+
+    /// Blocks until the token is available, or for at most `timeout`,
+    /// consuming the token if it was. Returns whether it was consumed.
+    pub fn park_timeout(&self, timeout: Duration) -> bool {
+        park(&self.state, Deadline::after(timeout))
+    }
+
+    /// Blocks until the token is available, or until `deadline` at the latest,
+    /// consuming the token if it was. Returns whether it was consumed.
+    pub fn park_deadline(&self, deadline: Instant) -> bool {
+        park(&self.state, Deadline::at(deadline))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for OwnedParker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for OwnedParker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedParker").finish_non_exhaustive()
+    }
+}
+
+/// A handle that unparks an [`OwnedParker`], which may be cloned and sent to other threads.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct OwnedUnparker {
+    /// The state of the parker.
+    state: Arc<AtomicU32>,
+}
+
+#[cfg(feature = "std")]
+impl OwnedUnparker {
+    /// Makes the token of the parker available, waking its thread if it is parked.
+    pub fn unpark(&self) {
+        unpark(&self.state);
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for OwnedUnparker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedUnparker").finish_non_exhaustive()
+    }
+}
+
+/// Blocks until the token in `state` is consumed, or until `deadline`. Returns whether it was.
+fn park(state: &AtomicU32, deadline: Deadline) -> bool {
+    // Either consumes the token, or marks the thread as parked.
+    if state.fetch_sub(1, Ordering::Acquire) == NOTIFIED {
+        return true;
+    }
+
+    loop {
+        if !wait(state, PARKED, deadline) {
+            return state.swap(EMPTY, Ordering::Acquire) == NOTIFIED;
+        }
+
+        // Keep waiting after a spurious wakeup.
+        if state
+            .compare_exchange(NOTIFIED, EMPTY, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            return true;
+        }
+    }
+}
+
+/// Makes the token in `state` available, waking its thread if it is parked.
+fn unpark(state: &AtomicU32) {
+    if state.swap(NOTIFIED, Ordering::Release) == PARKED {
+        state.notify_one();
+    }
+}
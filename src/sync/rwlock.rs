@@ -0,0 +1,847 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    hint::spin_loop,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use super::{Deadline, notify_bitset, wait_bitset};
+
+/// The bits of the state that count the read locks, including the upgradable one.
+const READERS: u32 = (1 << 26) - 1;
+
+/// A single read lock in the state.
+const READER: u32 = 1;
+
+/// Set in the state while an upgradable read lock is held.
+const UPGRADABLE: u32 = 1 << 26;
+
+/// Set in the state while the write lock is held.
+const WRITER: u32 = 1 << 27;
+
+/// Set in the state when writers may be waiting.
+const WRITERS_WAITING: u32 = 1 << 28;
+
+/// Set in the state when threads may be waiting for an upgradable read lock.
+const UPGRADABLE_WAITING: u32 = 1 << 29;
+
+/// Set in the state while the holder of the upgradable
+/// read lock waits for the other readers to leave.
+const UPGRADING: u32 = 1 << 30;
+
+/// The bit of the waiting readers word that flips whenever a writer
+/// hands the lock to the waiting readers, with [`RwLockPolicy::PhaseFair`].
+const PHASE: u32 = 1 << 31;
+
+/// The bits of the waiting readers word that count the waiting readers.
+const WAITING: u32 = !PHASE;
+
+/// The wait mask of readers.
+const READ_MASK: u32 = 1;
+
+/// The wait mask of writers.
+const WRITE_MASK: u32 = 2;
+
+/// The wait mask of threads waiting for an upgradable read lock.
+const UPGRADABLE_MASK: u32 = 4;
+
+/// The wait mask of the holder of the upgradable read lock, while it upgrades.
+const UPGRADE_MASK: u32 = 8;
+
+/// The number of times to check the state before sleeping, while another thread holds the lock.
+const SPIN_LIMIT: u32 = 100;
+
+/// Decides which threads acquire an [`RwLock`] when readers and writers compete for it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RwLockPolicy {
+    /// New readers wait while a writer is waiting,
+    /// so that a stream of readers cannot starve writers.
+    #[default]
+    WriterPreferring,
+    /// Readers acquire the lock whenever no writer holds it,
+    /// which allows the most concurrency, but may starve writers.
+    ReaderPreferring,
+    /// Readers and writers take turns: new readers wait while a writer is waiting,
+    /// and when a writer releases the lock, it hands it to every reader that was
+    /// waiting, before the next writer. Neither readers nor writers can be starved.
+    PhaseFair,
+}
+
+/// A reader-writer lock built on [`AtomicWait`](crate::AtomicWait).
+///
+/// The lock is a single [`AtomicU32`] that counts the readers and marks the writer,
+/// along with a second word that counts the waiting readers. Readers, writers, and
+/// upgrading readers wait with different bitsets on Linux, so that they are only woken
+/// when they may be able to proceed. On other platforms, they are all woken together.
+///
+/// Besides read and write locks, one thread at a time may hold an upgradable read lock,
+/// which coexists with other readers, and can be atomically upgraded to a write lock.
+/// The [`RwLockPolicy`] decides the order in which waiting readers and writers acquire it.
+pub struct RwLock<T: ?Sized> {
+    /// The lock state.
+    state: AtomicU32,
+    /// The number of waiting readers, along with the [`PHASE`] bit.
+    readers_waiting: AtomicU32,
+    /// The policy for ordering readers and writers.
+    policy: RwLockPolicy,
+    /// The protected data.
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    /// Creates a new, unlocked, writer-preferring lock.
+    pub const fn new(value: T) -> Self {
+        Self::with_policy(value, RwLockPolicy::WriterPreferring)
+    }
+
+    /// Creates a new, unlocked lock with the given policy.
+    pub const fn with_policy(value: T, policy: RwLockPolicy) -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            readers_waiting: AtomicU32::new(0),
+            policy,
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the lock, returning the protected data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Gets the policy for ordering readers and writers.
+    pub fn policy(&self) -> RwLockPolicy {
+        self.policy
+    }
+
+    /// Acquires a read lock, blocking until it is available.
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        if !self.try_read_fast() {
+            self.read_contended(Deadline::NEVER);
+        }
+
+        RwLockReadGuard::new(self)
+    }
+
+    /// Attempts to acquire a read lock without blocking.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        self.try_read_fast().then(|| RwLockReadGuard::new(self))
+    }
+
+    /// Attempts to acquire a read lock, blocking for at most `timeout`.
+    #[cfg(feature = "std")]
+    pub fn try_read_for(&self, timeout: Duration) -> Option<RwLockReadGuard<'_, T>> {
+        (self.try_read_fast() || self.read_contended(Deadline::after(timeout)))
+            .then(|| RwLockReadGuard::new(self))
+    }
+
+    /// Attempts to acquire a read lock, blocking until `deadline` at the latest.
+    #[cfg(feature = "std")]
+    pub fn try_read_until(&self, deadline: Instant) -> Option<RwLockReadGuard<'_, T>> {
+        (self.try_read_fast() || self.read_contended(Deadline::at(deadline)))
+            .then(|| RwLockReadGuard::new(self))
+    }
+
+    /// Acquires the write lock, blocking until it is available.
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        if !self.try_write_fast() {
+            self.write_contended(Deadline::NEVER);
+        }
+
+        RwLockWriteGuard::new(self)
+    }
+
+    /// Attempts to acquire the write lock without blocking.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        self.try_write_fast().then(|| RwLockWriteGuard::new(self))
+    }
+
+    /// Attempts to acquire the write lock, blocking for at most `timeout`.
+    #[cfg(feature = "std")]
+    pub fn try_write_for(&self, timeout: Duration) -> Option<RwLockWriteGuard<'_, T>> {
+        (self.try_write_fast() || self.write_contended(Deadline::after(timeout)))
+            .then(|| RwLockWriteGuard::new(self))
+    }
+
+    /// Attempts to acquire the write lock, blocking until `deadline` at the latest.
+    #[cfg(feature = "std")]
+    pub fn try_write_until(&self, deadline: Instant) -> Option<RwLockWriteGuard<'_, T>> {
+        (self.try_write_fast() || self.write_contended(Deadline::at(deadline)))
+            .then(|| RwLockWriteGuard::new(self))
+    }
+
+    /// Acquires the upgradable read lock, blocking until it is available.
+    pub fn upgradable_read(&self) -> RwLockUpgradableReadGuard<'_, T> {
+        if !self.try_upgradable_read_fast() {
+            self.upgradable_read_contended(Deadline::NEVER);
+        }
+
+        RwLockUpgradableReadGuard::new(self)
+    }
+
+    /// Attempts to acquire the upgradable read lock without blocking.
+    pub fn try_upgradable_read(&self) -> Option<RwLockUpgradableReadGuard<'_, T>> {
+        self.try_upgradable_read_fast()
+            .then(|| RwLockUpgradableReadGuard::new(self))
+    }
+
+    /// Attempts to acquire the upgradable read lock, blocking for at most `timeout`.
+    #[cfg(feature = "std")]
+    pub fn try_upgradable_read_for(
+        &self,
+        timeout: Duration,
+    ) -> Option<RwLockUpgradableReadGuard<'_, T>> {
+        (self.try_upgradable_read_fast()
+            || self.upgradable_read_contended(Deadline::after(timeout)))
+        .then(|| RwLockUpgradableReadGuard::new(self))
+    }
+
+    /// Attempts to acquire the upgradable read lock, blocking until `deadline` at the latest.
+    #[cfg(feature = "std")]
+    pub fn try_upgradable_read_until(
+        &self,
+        deadline: Instant,
+    ) -> Option<RwLockUpgradableReadGuard<'_, T>> {
+        (self.try_upgradable_read_fast() || self.upgradable_read_contended(Deadline::at(deadline)))
+            .then(|| RwLockUpgradableReadGuard::new(self))
+    }
+
+    /// Gets a mutable reference to the protected data.
+    /// No locking is needed, since the lock is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Gets whether a reader may acquire the lock in `state`.
+    fn can_read(&self, state: u32) -> bool {
+        state & WRITER == 0
+            && state & READERS != READERS
+            && (self.policy == RwLockPolicy::ReaderPreferring
+                || state & (WRITERS_WAITING | UPGRADING) == 0)
+    }
+
+    /// Gets whether a thread may acquire the upgradable read lock in `state`.
+    fn can_read_upgradable(&self, state: u32) -> bool {
+        state & UPGRADABLE == 0 && self.can_read(state)
+    }
+
+    /// Gets whether a writer may acquire the lock in `state`.
+    fn can_write(state: u32) -> bool {
+        state & (READERS | WRITER) == 0
+    }
+
+    /// Attempts to acquire a read lock, if the policy allows it.
+    fn try_read_fast(&self) -> bool {
+        self.state
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |x| {
+                self.can_read(x).then_some(x + READER)
+            })
+            .is_ok()
+    }
+
+    /// Attempts to acquire the write lock.
+    fn try_write_fast(&self) -> bool {
+        self.state
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |x| {
+                Self::can_write(x).then_some(x | WRITER)
+            })
+            .is_ok()
+    }
+
+    /// Attempts to acquire the upgradable read lock, if the policy allows it.
+    fn try_upgradable_read_fast(&self) -> bool {
+        self.state
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |x| {
+                self.can_read_upgradable(x)
+                    .then_some(x + READER + UPGRADABLE)
+            })
+            .is_ok()
+    }
+
+    /// Attempts to upgrade the held upgradable read lock to the write lock.
+    fn try_upgrade_fast(&self) -> bool {
+        self.state
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |x| {
+                (x & READERS == READER).then(|| (x - READER - UPGRADABLE) & !UPGRADING | WRITER)
+            })
+            .is_ok()
+    }
+
+    /// Spins for a short while, in case the lock is about to be released.
+    fn spin(&self) -> u32 {
+        let mut state = self.state.load(Ordering::Relaxed);
+        for _ in 0..SPIN_LIMIT {
+            if state & (READERS | WRITER) == 0
+                || state & (WRITERS_WAITING | UPGRADABLE_WAITING | UPGRADING) != 0
+                || self.readers_waiting.load(Ordering::Relaxed) & WAITING != 0
+            {
+                break;
+            }
+
+            spin_loop();
+            state = self.state.load(Ordering::Relaxed);
+        }
+
+        state
+    }
+
+    /// Blocks until a read lock is acquired, or until `deadline`.
+    /// Returns whether the lock was acquired.
+    #[cold]
+    fn read_contended(&self, deadline: Deadline) -> bool {
+        // The phase in which this thread started waiting,
+        // once it has been counted as a waiting reader.
+        let mut phase = None;
+        let mut state = self.spin();
+        loop {
+            if self.can_read(state) {
+                match self.state.compare_exchange_weak(
+                    state,
+                    state + READER,
+                    Ordering::Acquire,
+                    Ordering::SeqCst,
+                ) {
+                    Ok(_) => {
+                        if let Some(phase) = phase
+                            && !self.stop_waiting(phase)
+                        {
+                            // A writer handed this thread a second read lock meanwhile.
+                            self.read_unlock();
+                        }
+
+                        return true;
+                    }
+                    Err(x) => {
+                        state = x;
+                        continue;
+                    }
+                }
+            }
+
+            match phase {
+                None => {
+                    // Check the state again after being counted, so
+                    // that unlocking writers are sure to notice it.
+                    phase = Some(self.readers_waiting.fetch_add(1, Ordering::SeqCst) & PHASE);
+                    state = self.state.load(Ordering::SeqCst);
+                    continue;
+                }
+                Some(phase) if self.readers_waiting.load(Ordering::Acquire) & PHASE != phase => {
+                    // A writer handed this thread the lock, and
+                    // is about to clear its bit from the state.
+                    if state & WRITER == 0 {
+                        return true;
+                    }
+                }
+                Some(_) => {}
+            }
+
+            if !wait_bitset(&self.state, state, READ_MASK, deadline) {
+                if let Some(phase) = phase
+                    && !self.stop_waiting(phase)
+                {
+                    while self.state.load(Ordering::Acquire) & WRITER != 0 {
+                        spin_loop();
+                    }
+
+                    return true;
+                }
+
+                return false;
+            }
+
+            state = self.state.load(Ordering::SeqCst);
+        }
+    }
+
+    /// Stops counting this thread as a reader that has waited since `phase`.
+    /// Returns `false` if a writer has handed it a read lock in the meantime.
+    fn stop_waiting(&self, phase: u32) -> bool {
+        self.readers_waiting
+            .fetch_update(Ordering::Relaxed, Ordering::Acquire, |x| {
+                (x & PHASE == phase).then(|| x - 1)
+            })
+            .is_ok()
+    }
+
+    /// Blocks until the write lock is acquired, or until `deadline`.
+    /// Returns whether the lock was acquired.
+    #[cold]
+    fn write_contended(&self, deadline: Deadline) -> bool {
+        // Writers that have waited cannot know whether others are
+        // still waiting, so they keep the lock marked as contended.
+        let mut waited = 0;
+        let mut state = self.spin();
+        loop {
+            if Self::can_write(state) {
+                match self.state.compare_exchange_weak(
+                    state,
+                    state | WRITER | waited,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return true,
+                    Err(x) => {
+                        state = x;
+                        continue;
+                    }
+                }
+            }
+
+            if state & WRITERS_WAITING == 0 {
+                if let Err(x) = self.state.compare_exchange_weak(
+                    state,
+                    state | WRITERS_WAITING,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    state = x;
+                    continue;
+                }
+
+                state |= WRITERS_WAITING;
+            }
+
+            if !wait_bitset(&self.state, state, WRITE_MASK, deadline) {
+                // Other writers may still be waiting, and this thread may have been
+                // woken in place of one, but it cannot tell. So rather than leaving
+                // readers held back, clear the mark and wake the other writers,
+                // which set it again if they still need to wait.
+                self.state.fetch_and(!WRITERS_WAITING, Ordering::SeqCst);
+                notify_bitset(&self.state, WRITE_MASK, i32::MAX as u32);
+                self.wake_readers();
+                return false;
+            }
+
+            waited = WRITERS_WAITING;
+            state = self.state.load(Ordering::Relaxed);
+        }
+    }
+
+    /// Blocks until the upgradable read lock is acquired, or until `deadline`.
+    /// Returns whether the lock was acquired.
+    #[cold]
+    fn upgradable_read_contended(&self, deadline: Deadline) -> bool {
+        let mut state = self.spin();
+        loop {
+            if self.can_read_upgradable(state) {
+                match self.state.compare_exchange_weak(
+                    state,
+                    state + READER + UPGRADABLE,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return true,
+                    Err(x) => {
+                        state = x;
+                        continue;
+                    }
+                }
+            }
+
+            if state & UPGRADABLE_WAITING == 0 {
+                if let Err(x) = self.state.compare_exchange_weak(
+                    state,
+                    state | UPGRADABLE_WAITING,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    state = x;
+                    continue;
+                }
+
+                state |= UPGRADABLE_WAITING;
+            }
+
+            // Threads waiting for the upgradable lock are all woken
+            // together, so timing out cannot swallow another's wakeup.
+            if !wait_bitset(&self.state, state, UPGRADABLE_MASK, deadline) {
+                return false;
+            }
+
+            state = self.state.load(Ordering::Relaxed);
+        }
+    }
+
+    /// Blocks until the held upgradable read lock is upgraded to the
+    /// write lock, or until `deadline`. Returns whether it was upgraded.
+    fn upgrade_contended(&self, deadline: Deadline) -> bool {
+        let mut state = self.state.load(Ordering::Relaxed);
+        loop {
+            if state & READERS == READER {
+                match self.state.compare_exchange_weak(
+                    state,
+                    (state - READER - UPGRADABLE) & !UPGRADING | WRITER,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return true,
+                    Err(x) => {
+                        state = x;
+                        continue;
+                    }
+                }
+            }
+
+            if state & UPGRADING == 0 {
+                if let Err(x) = self.state.compare_exchange_weak(
+                    state,
+                    state | UPGRADING,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    state = x;
+                    continue;
+                }
+
+                state |= UPGRADING;
+            }
+
+            if !wait_bitset(&self.state, state, UPGRADE_MASK, deadline) {
+                // Readers may have been held back while this thread was upgrading.
+                self.state.fetch_and(!UPGRADING, Ordering::SeqCst);
+                self.wake_readers();
+                return false;
+            }
+
+            state = self.state.load(Ordering::Relaxed);
+        }
+    }
+
+    /// Releases a read lock.
+    fn read_unlock(&self) {
+        let state = self.state.fetch_sub(READER, Ordering::SeqCst) - READER;
+        if state & (READERS | WRITER) == 0 {
+            self.wake_after_release(state);
+        } else if state & READERS == READER && state & UPGRADING != 0 {
+            notify_bitset(&self.state, UPGRADE_MASK, 1);
+        }
+    }
+
+    /// Releases the upgradable read lock.
+    fn upgradable_unlock(&self) {
+        let state =
+            self.state.fetch_sub(READER + UPGRADABLE, Ordering::SeqCst) - READER - UPGRADABLE;
+        if state & (READERS | WRITER) == 0 {
+            self.wake_after_release(state);
+        } else {
+            self.wake_upgradable(state);
+        }
+    }
+
+    /// Releases the write lock.
+    fn write_unlock(&self) {
+        if self.policy == RwLockPolicy::PhaseFair
+            && self.readers_waiting.load(Ordering::SeqCst) & WAITING != 0
+        {
+            // Hand the lock to every waiting reader, starting a new phase.
+            let waiting = self
+                .readers_waiting
+                .fetch_update(Ordering::Release, Ordering::Relaxed, |x| {
+                    Some((x ^ PHASE) & PHASE)
+                })
+                .unwrap_or_else(|x| x)
+                & WAITING;
+
+            if waiting != 0 {
+                let state = self
+                    .state
+                    .fetch_update(Ordering::Release, Ordering::Relaxed, |x| {
+                        Some((x & !WRITER) + waiting * READER)
+                    })
+                    .unwrap_or_else(|x| x);
+                notify_bitset(&self.state, READ_MASK, i32::MAX as u32);
+                self.wake_upgradable(state);
+                return;
+            }
+        }
+
+        let state = self.state.fetch_and(!WRITER, Ordering::SeqCst) & !WRITER;
+        self.wake_after_release(state);
+    }
+
+    /// Wakes the threads that may acquire the lock, now that it is free.
+    fn wake_after_release(&self, state: u32) {
+        if state & WRITERS_WAITING != 0 {
+            self.state.fetch_and(!WRITERS_WAITING, Ordering::Relaxed);
+            if notify_bitset(&self.state, WRITE_MASK, 1)
+                && self.policy != RwLockPolicy::ReaderPreferring
+            {
+                // Readers wait for the writer, which wakes them once it is done.
+                return;
+            }
+        }
+
+        self.wake_readers();
+    }
+
+    /// Wakes every thread that is waiting for a read lock or the upgradable read lock.
+    fn wake_readers(&self) {
+        if self.readers_waiting.load(Ordering::SeqCst) & WAITING != 0 {
+            notify_bitset(&self.state, READ_MASK, i32::MAX as u32);
+        }
+
+        self.wake_upgradable(self.state.load(Ordering::Relaxed));
+    }
+
+    /// Wakes every thread that is waiting for the upgradable read lock, if there may be any.
+    fn wake_upgradable(&self, state: u32) {
+        if state & UPGRADABLE_WAITING != 0 {
+            self.state.fetch_and(!UPGRADABLE_WAITING, Ordering::Relaxed);
+            notify_bitset(&self.state, UPGRADABLE_MASK, i32::MAX as u32);
+        }
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("RwLock");
+        match self.try_read() {
+            Some(guard) => d.field("data", &&*guard),
+            None => d.field("data", &format_args!("<locked>")),
+        };
+        d.field("policy", &self.policy);
+        d.finish_non_exhaustive()
+    }
+}
+
+/// Provides shared access to the data of an [`RwLock`], and releases the read lock when dropped.
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    /// The locked lock.
+    lock: &'a RwLock<T>,
+    /// Prevents the guard from being sent to other threads, like `std`'s guards.
+    marker: PhantomData<*const ()>,
+}
+
+impl<'a, T: ?Sized> RwLockReadGuard<'a, T> {
+    /// Creates a guard for a read lock that the current thread has acquired.
+    fn new(lock: &'a RwLock<T>) -> Self {
+        Self {
+            lock,
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for RwLockReadGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for RwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.read_unlock();
+    }
+}
+
+/// Provides exclusive access to the data of an [`RwLock`], and releases the write lock when dropped.
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    /// The locked lock.
+    lock: &'a RwLock<T>,
+    /// Prevents the guard from being sent to other threads, like `std`'s guards.
+    marker: PhantomData<*const ()>,
+}
+
+impl<'a, T: ?Sized> RwLockWriteGuard<'a, T> {
+    /// Creates a guard for a write lock that the current thread has acquired.
+    fn new(lock: &'a RwLock<T>) -> Self {
+        Self {
+            lock,
+            marker: PhantomData,
+        }
+    }
+
+    /// Atomically turns the write lock into a read lock, so that
+    /// other readers may acquire the lock, but no writer can.
+    pub fn downgrade(guard: Self) -> RwLockReadGuard<'a, T> {
+        let lock = ManuallyDrop::new(guard).lock;
+        lock.state.fetch_sub(WRITER - READER, Ordering::Release);
+        lock.wake_readers();
+        RwLockReadGuard::new(lock)
+    }
+
+    /// Atomically turns the write lock into the upgradable read lock.
+    pub fn downgrade_to_upgradable(guard: Self) -> RwLockUpgradableReadGuard<'a, T> {
+        let lock = ManuallyDrop::new(guard).lock;
+        lock.state
+            .fetch_sub(WRITER - READER - UPGRADABLE, Ordering::Release);
+        lock.wake_readers();
+        RwLockUpgradableReadGuard::new(lock)
+    }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for RwLockWriteGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for RwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.write_unlock();
+    }
+}
+
+/// Provides shared access to the data of an [`RwLock`] through its upgradable read lock,
+/// which may be upgraded to the write lock, and releases it when dropped.
+pub struct RwLockUpgradableReadGuard<'a, T: ?Sized> {
+    /// The locked lock.
+    lock: &'a RwLock<T>,
+    /// Prevents the guard from being sent to other threads, like `std`'s guards.
+    marker: PhantomData<*const ()>,
+}
+
+impl<'a, T: ?Sized> RwLockUpgradableReadGuard<'a, T> {
+    /// Creates a guard for an upgradable read lock that the current thread has acquired.
+    fn new(lock: &'a RwLock<T>) -> Self {
+        Self {
+            lock,
+            marker: PhantomData,
+        }
+    }
+
+    /// Upgrades to the write lock, blocking until the other readers have released the lock.
+    pub fn upgrade(guard: Self) -> RwLockWriteGuard<'a, T> {
+        let lock = ManuallyDrop::new(guard).lock;
+        if !lock.try_upgrade_fast() {
+            lock.upgrade_contended(Deadline::NEVER);
+        }
+
+        RwLockWriteGuard::new(lock)
+    }
+
+    /// Attempts to upgrade to the write lock without blocking,
+    /// returning the guard if other readers hold the lock.
+    pub fn try_upgrade(guard: Self) -> Result<RwLockWriteGuard<'a, T>, Self> {
+        if guard.lock.try_upgrade_fast() {
+            Ok(RwLockWriteGuard::new(ManuallyDrop::new(guard).lock))
+        } else {
+            Err(guard)
+        }
+    }
+
+    /// Attempts to upgrade to the write lock, blocking for at most `timeout`.
+    #[cfg(feature = "std")]
+    pub fn try_upgrade_for(
+        guard: Self,
+        timeout: Duration,
+    ) -> Result<RwLockWriteGuard<'a, T>, Self> {
+        Self::try_upgrade_with(guard, Deadline::after(timeout))
+    }
+
+    /// Attempts to upgrade to the write lock, blocking until `deadline` at the latest.
+    #[cfg(feature = "std")]
+    pub fn try_upgrade_until(
+        guard: Self,
+        deadline: Instant,
+    ) -> Result<RwLockWriteGuard<'a, T>, Self> {
+        Self::try_upgrade_with(guard, Deadline::at(deadline))
+    }
+
+    /// Atomically turns the upgradable read lock into an ordinary read lock.
+    pub fn downgrade(guard: Self) -> RwLockReadGuard<'a, T> {
+        let lock = ManuallyDrop::new(guard).lock;
+        let state = lock.state.fetch_sub(UPGRADABLE, Ordering::Release) - UPGRADABLE;
+        lock.wake_upgradable(state);
+        RwLockReadGuard::new(lock)
+    }
+
+    /// Attempts to upgrade to the write lock, blocking until `deadline` at the latest.
+    #[cfg(feature = "std")]
+    fn try_upgrade_with(guard: Self, deadline: Deadline) -> Result<RwLockWriteGuard<'a, T>, Self> {
+        if guard.lock.try_upgrade_fast() || guard.lock.upgrade_contended(deadline) {
+            Ok(RwLockWriteGuard::new(ManuallyDrop::new(guard).lock))
+        } else {
+            Err(guard)
+        }
+    }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for RwLockUpgradableReadGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for RwLockUpgradableReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockUpgradableReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for RwLockUpgradableReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockUpgradableReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.upgradable_unlock();
+    }
+}
@@ -0,0 +1,211 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use super::{Deadline, notify_many, wait};
+use crate::AtomicWait;
+
+/// A counting semaphore built on [`AtomicWait`].
+///
+/// The available permits are an [`AtomicU32`], upon which waiters sleep, along with a count
+/// of the waiters. Threads may acquire several permits at once. Releasing permits wakes at
+/// most as many waiters as there are permits available, since no more could proceed. On
+/// platforms that can only wake one or all waiters, releasing several permits wakes them all.
+///
+/// A waiter that is woken but needs more permits than are available passes the
+/// wakeup on to another waiter, so that waiters which need fewer are not stranded.
+pub struct Semaphore {
+    /// The number of available permits.
+    permits: AtomicU32,
+    /// The number of threads waiting for permits.
+    waiters: AtomicU32,
+    /// Counts the times that permits were added while threads were waiting.
+    releases: AtomicU32,
+}
+
+impl Semaphore {
+    /// Creates a new semaphore with `permits` available permits.
+    pub const fn new(permits: u32) -> Self {
+        Self {
+            permits: AtomicU32::new(permits),
+            waiters: AtomicU32::new(0),
+            releases: AtomicU32::new(0),
+        }
+    }
+
+    /// Gets the number of available permits.
+    pub fn available_permits(&self) -> u32 {
+        self.permits.load(Ordering::Relaxed)
+    }
+
+    /// Acquires `n` permits, blocking until they are available. This
+    /// blocks forever if the semaphore never has `n` permits available.
+    pub fn acquire(&self, n: u32) -> SemaphorePermit<'_> {
+        if !self.try_take(n) {
+            self.acquire_contended(n, Deadline::NEVER);
+        }
+
+        SemaphorePermit::new(self, n)
+    }
+
+    /// Attempts to acquire `n` permits without blocking.
+    pub fn try_acquire(&self, n: u32) -> Option<SemaphorePermit<'_>> {
+        self.try_take(n).then(|| SemaphorePermit::new(self, n))
+    }
+
+    /// Attempts to acquire `n` permits, blocking for at most `timeout`.
+    #[cfg(feature = "std")]
+    pub fn acquire_timeout(&self, n: u32, timeout: Duration) -> Option<SemaphorePermit<'_>> {
+        (self.try_take(n) || self.acquire_contended(n, Deadline::after(timeout)))
+            .then(|| SemaphorePermit::new(self, n))
+    }
+
+    /// Attempts to acquire `n` permits, blocking until `deadline` at the latest.
+    #[cfg(feature = "std")]
+    pub fn acquire_until(&self, n: u32, deadline: Instant) -> Option<SemaphorePermit<'_>> {
+        (self.try_take(n) || self.acquire_contended(n, Deadline::at(deadline)))
+            .then(|| SemaphorePermit::new(self, n))
+    }
+
+    /// Adds `n` permits to the semaphore, waking waiters that may now acquire them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of available permits would exceed [`u32::MAX`].
+    pub fn add_permits(&self, n: u32) {
+        let permits = self
+            .permits
+            .fetch_update(Ordering::SeqCst, Ordering::Relaxed, |x| x.checked_add(n))
+            .expect("semaphore permits overflowed")
+            + n;
+
+        let waiters = self.waiters.load(Ordering::SeqCst);
+        if n != 0 && waiters != 0 {
+            self.releases.fetch_add(1, Ordering::Relaxed);
+            notify_many(&self.permits, waiters.min(permits));
+        }
+    }
+
+    /// Takes `n` permits if they are available.
+    fn try_take(&self, n: u32) -> bool {
+        self.permits
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |x| x.checked_sub(n))
+            .is_ok()
+    }
+
+    /// Blocks until `n` permits are taken, or until `deadline`.
+    /// Returns whether they were taken.
+    #[cold]
+    fn acquire_contended(&self, n: u32, deadline: Deadline) -> bool {
+        // Count this thread as a waiter before checking the permits again,
+        // so that threads adding permits are sure to notice it.
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+
+        // The release after which this thread last passed a wakeup on.
+        let mut passed = None;
+        let mut woken = false;
+        let mut permits = self.permits.load(Ordering::SeqCst);
+        let acquired = loop {
+            if permits >= n {
+                match self.permits.compare_exchange_weak(
+                    permits,
+                    permits - n,
+                    Ordering::Acquire,
+                    Ordering::SeqCst,
+                ) {
+                    Ok(_) => break true,
+                    Err(x) => {
+                        permits = x;
+                        continue;
+                    }
+                }
+            }
+
+            // Passing the wakeup on at most once after each release stops
+            // waiters that all need more permits from waking each other forever.
+            if woken && permits > 0 {
+                let release = self.releases.load(Ordering::Relaxed);
+                if passed != Some(release) {
+                    passed = Some(release);
+                    self.permits.notify_one();
+                }
+            }
+
+            if !wait(&self.permits, permits, deadline) {
+                break false;
+            }
+
+            woken = true;
+            permits = self.permits.load(Ordering::SeqCst);
+        };
+
+        self.waiters.fetch_sub(1, Ordering::Relaxed);
+
+        // This thread may have been woken in place of another waiter before timing out.
+        if !acquired && self.permits.load(Ordering::Relaxed) > 0 {
+            self.permits.notify_one();
+        }
+
+        acquired
+    }
+}
+
+impl Default for Semaphore {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Semaphore")
+            .field("permits", &self.available_permits())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Permits acquired from a [`Semaphore`], which are returned to it when dropped.
+#[must_use = "if unused the permits are immediately released"]
+pub struct SemaphorePermit<'a> {
+    /// The semaphore that the permits belong to.
+    semaphore: &'a Semaphore,
+    /// The number of permits held.
+    permits: u32,
+}
+
+impl<'a> SemaphorePermit<'a> {
+    /// Creates a guard for `permits` permits that the current thread has acquired.
+    fn new(semaphore: &'a Semaphore, permits: u32) -> Self {
+        Self { semaphore, permits }
+    }
+
+    /// Gets the number of permits held.
+    pub fn num_permits(&self) -> u32 {
+        self.permits
+    }
+
+    /// Keeps the permits from being returned to the semaphore,
+    /// which permanently reduces the number of permits.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit")
+            .field("permits", &self.permits)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        if self.permits != 0 {
+            self.semaphore.add_permits(self.permits);
+        }
+    }
+}
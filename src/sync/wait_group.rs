@@ -0,0 +1,138 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicU32, Ordering},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use super::{Deadline, wait};
+use crate::AtomicWait;
+
+/// The bits of the state that count the outstanding tasks.
+const COUNT: u32 = !WAITING;
+
+/// Set in the state while threads may be waiting for the count to reach zero.
+const WAITING: u32 = 1 << 31;
+
+/// A wait group built on [`AtomicWait`], which blocks threads until a
+/// number of tasks have finished, like the `WaitGroup` of Go.
+///
+/// The count of outstanding tasks and whether threads are waiting are packed into an
+/// [`AtomicU32`], upon which waiters sleep, so that finishing the last task only notifies
+/// if a thread may be waiting. Once the count returns to zero, the group may be reused,
+/// although tasks for the next round must not be added until every waiter has returned.
+pub struct WaitGroup {
+    /// The number of outstanding tasks, and whether threads may be waiting.
+    state: AtomicU32,
+}
+
+impl WaitGroup {
+    /// Creates a new wait group without any outstanding tasks.
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(0),
+        }
+    }
+
+    /// Gets the number of outstanding tasks.
+    pub fn count(&self) -> u32 {
+        self.state.load(Ordering::Relaxed) & COUNT
+    }
+
+    /// Adds `n` outstanding tasks to the group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there would be more than 2<sup>31</sup> - 1 outstanding tasks.
+    pub fn add(&self, n: u32) {
+        self.state
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+                (x & COUNT)
+                    .checked_add(n)
+                    .filter(|&count| count <= COUNT)
+                    .map(|count| count | x & WAITING)
+            })
+            .expect("wait group counter overflowed");
+    }
+
+    /// Marks one task as finished, releasing every waiter if it was the last.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group has no outstanding tasks.
+    pub fn done(&self) {
+        let state = self
+            .state
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |x| {
+                (x & COUNT != 0).then(|| x - 1)
+            })
+            .expect("finished a task of a wait group without outstanding tasks");
+
+        if state == WAITING | 1 {
+            // Waiters that find tasks were added since set the flag again.
+            self.state.fetch_and(!WAITING, Ordering::Relaxed);
+            self.state.notify_all();
+        }
+    }
+
+    /// Blocks until there are no outstanding tasks.
+    pub fn wait(&self) {
+        self.wait_with(Deadline::NEVER);
+    }
+
+    /// Blocks until there are no outstanding tasks, or for at most `timeout`.
+    /// Returns whether every task finished.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.wait_with(Deadline::after(timeout))
+    }
+
+    /// Blocks until there are no outstanding tasks, or until `deadline` at the latest.
+    /// Returns whether every task finished.
+    #[cfg(feature = "std")]
+    pub fn wait_until(&self, deadline: Instant) -> bool {
+        self.wait_with(Deadline::at(deadline))
+    }
+
+    /// Blocks until the count reaches zero, or until `deadline`. Returns whether it did.
+    fn wait_with(&self, deadline: Deadline) -> bool {
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            if state & COUNT == 0 {
+                return true;
+            }
+
+            if state & WAITING == 0
+                && let Err(x) = self.state.compare_exchange_weak(
+                    state,
+                    state | WAITING,
+                    Ordering::Acquire,
+                    Ordering::Acquire,
+                )
+            {
+                state = x;
+                continue;
+            }
+
+            if !wait(&self.state, state | WAITING, deadline) {
+                return self.state.load(Ordering::Acquire) & COUNT == 0;
+            }
+
+            state = self.state.load(Ordering::Acquire);
+        }
+    }
+}
+
+impl Default for WaitGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for WaitGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitGroup")
+            .field("count", &self.count())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,228 @@
+use core::sync::atomic::AtomicU32;
+
+/// Invokes a system call with up to six arguments.
+/// Returns the result, which is a negated `errno` value on failure.
+#[cfg(target_arch = "x86_64")]
+pub unsafe fn syscall6(number: libc::c_long, args: [usize; 6]) -> isize {
+    let result;
+    unsafe {
+        core::arch::asm!(
+            "syscall",
+            inlateout("rax") number as isize => result,
+            in("rdi") args[0],
+            in("rsi") args[1],
+            in("rdx") args[2],
+            in("r10") args[3],
+            in("r8") args[4],
+            in("r9") args[5],
+            lateout("rcx") _,
+            lateout("r11") _,
+            options(nostack),
+        );
+    }
+    result
+}
+
+/// Invokes a system call with up to six arguments.
+/// Returns the result, which is a negated `errno` value on failure.
+#[cfg(target_arch = "aarch64")]
+pub unsafe fn syscall6(number: libc::c_long, args: [usize; 6]) -> isize {
+    let result;
+    unsafe {
+        core::arch::asm!(
+            "svc 0",
+            in("x8") number as isize,
+            inlateout("x0") args[0] as isize => result,
+            in("x1") args[1],
+            in("x2") args[2],
+            in("x3") args[3],
+            in("x4") args[4],
+            in("x5") args[5],
+            options(nostack),
+        );
+    }
+    result
+}
+
+/// Invokes a system call with up to six arguments.
+/// Returns the result, which is a negated `errno` value on failure.
+#[cfg(target_arch = "riscv64")]
+pub unsafe fn syscall6(number: libc::c_long, args: [usize; 6]) -> isize {
+    let result;
+    unsafe {
+        core::arch::asm!(
+            "ecall",
+            in("a7") number as isize,
+            inlateout("a0") args[0] as isize => result,
+            in("a1") args[1],
+            in("a2") args[2],
+            in("a3") args[3],
+            in("a4") args[4],
+            in("a5") args[5],
+            options(nostack),
+        );
+    }
+    result
+}
+
+/// Invokes a system call with up to six arguments through `libc`,
+/// on architectures without an inline assembly implementation.
+/// Returns the result, which is a negated `errno` value on failure.
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+)))]
+pub unsafe fn syscall6(number: libc::c_long, args: [usize; 6]) -> isize {
+    unsafe {
+        let result = libc::syscall(number, args[0], args[1], args[2], args[3], args[4], args[5]);
+        if result == -1 {
+            #[cfg(target_os = "android")]
+            let errno = *libc::__errno();
+            #[cfg(not(target_os = "android"))]
+            let errno = *libc::__errno_location();
+            -(errno as isize)
+        } else {
+            result as isize
+        }
+    }
+}
+
+/// Performs the futex operation `op` on `uaddr`.
+pub unsafe fn futex(
+    uaddr: *const AtomicU32,
+    op: libc::c_int,
+    val: u32,
+    timeout: *const libc::timespec,
+    uaddr2: *const AtomicU32,
+    val3: u32,
+) -> isize {
+    unsafe {
+        syscall6(
+            libc::SYS_futex,
+            [
+                uaddr as usize,
+                op as usize,
+                val as usize,
+                timeout as usize,
+                uaddr2 as usize,
+                val3 as usize,
+            ],
+        )
+    }
+}
+
+/// Like `FUTEX_LOCK_PI`, but measures timeouts with `CLOCK_MONOTONIC` (Linux 5.14+).
+pub const FUTEX_LOCK_PI2: libc::c_int = 13;
+
+/// The `futex_wake` syscall (Linux 6.7+), which has the same number on every architecture.
+pub const SYS_FUTEX_WAKE: libc::c_long = 454;
+
+/// The `futex_wait` syscall (Linux 6.7+), which has the same number on every architecture.
+pub const SYS_FUTEX_WAIT: libc::c_long = 455;
+
+/// Indicates to the `futex2` interface that a futex is 32 bits wide.
+pub const FUTEX2_SIZE_U32: u32 = 0x02;
+
+/// Indicates to the `futex2` interface that a futex is followed by the
+/// ID of the NUMA node whose hash table it uses (Linux 6.16+).
+pub const FUTEX2_NUMA: u32 = 0x04;
+
+/// Indicates to the `futex2` interface that a futex is only used
+/// within this process, like `FUTEX_PRIVATE_FLAG`.
+pub const FUTEX2_PRIVATE: u32 = 128;
+
+/// Matches `futex2` waiters regardless of their bitset.
+pub const FUTEX_BITSET_MATCH_ANY: u64 = u32::MAX as u64;
+
+/// Counts the `fork`s that led to the current process, so that
+/// per-thread state which the kernel resets in children can be refreshed.
+#[cfg(feature = "std")]
+pub fn fork_generation() -> u32 {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    static GENERATION: AtomicU32 = AtomicU32::new(0);
+    static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn increment_generation() {
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    if !REGISTERED.load(Ordering::Acquire) && !REGISTERED.swap(true, Ordering::AcqRel) {
+        unsafe {
+            libc::pthread_atfork(None, None, Some(increment_generation));
+        }
+    }
+
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// Gets the kernel thread ID of the calling thread.
+#[cfg(feature = "std")]
+pub fn gettid() -> u32 {
+    use core::cell::Cell;
+
+    std::thread_local! {
+        /// The fork generation and ID of this thread, when they were last read.
+        /// The thread has a new ID in forked children.
+        static TID: Cell<(u32, u32)> = const { Cell::new((u32::MAX, 0)) };
+    }
+
+    let generation = fork_generation();
+    TID.with(|x| match x.get() {
+        (cached, tid) if cached == generation => tid,
+        _ => {
+            let tid = unsafe { syscall6(libc::SYS_gettid, [0; 6]) as u32 };
+            x.set((generation, tid));
+            tid
+        }
+    })
+}
+
+/// Gets the kernel thread ID of the calling thread.
+#[cfg(not(feature = "std"))]
+pub fn gettid() -> u32 {
+    unsafe { syscall6(libc::SYS_gettid, [0; 6]) as u32 }
+}
+
+/// Reads the current time of `clock`.
+pub fn clock_gettime(clock: libc::clockid_t) -> libc::timespec {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    unsafe {
+        syscall6(
+            libc::SYS_clock_gettime,
+            [clock as usize, &mut time as *mut _ as usize, 0, 0, 0, 0],
+        );
+    }
+
+    time
+}
+
+/// Gets the absolute time on `clock` at which `timeout` will have elapsed,
+/// saturating if it is not representable.
+pub fn deadline(clock: libc::clockid_t, timeout: core::time::Duration) -> libc::timespec {
+    let now = clock_gettime(clock);
+    let mut nanos = now.tv_nsec as u64 + timeout.subsec_nanos() as u64;
+    let mut secs = (now.tv_sec as u64).saturating_add(timeout.as_secs());
+    if nanos >= 1_000_000_000 {
+        nanos -= 1_000_000_000;
+        secs = secs.saturating_add(1);
+    }
+
+    libc::timespec {
+        tv_sec: secs.min(libc::time_t::MAX as u64) as libc::time_t,
+        tv_nsec: nanos as _,
+    }
+}
+
+/// Gets the time left on `clock` until `deadline`, or zero if it has passed.
+pub fn remaining(clock: libc::clockid_t, deadline: &libc::timespec) -> core::time::Duration {
+    let now = clock_gettime(clock);
+    let to_duration =
+        |x: &libc::timespec| core::time::Duration::new(x.tv_sec.max(0) as u64, x.tv_nsec as u32);
+    to_duration(deadline).saturating_sub(to_duration(&now))
+}
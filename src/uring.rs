@@ -0,0 +1,98 @@
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use io_uring::{opcode, squeue::Entry, types::FutexWaitV};
+
+use crate::{
+    platform::bucket_for_ptr,
+    syscall::{FUTEX_BITSET_MATCH_ANY, FUTEX2_PRIVATE, FUTEX2_SIZE_U32},
+};
+
+/// Creates a submission that completes once `atomic` is notified,
+/// or immediately with `-EAGAIN` if it does not hold `value`.
+///
+/// The submission is woken by [`AtomicWait::notify_one`](crate::AtomicWait::notify_one) and
+/// [`AtomicWait::notify_all`](crate::AtomicWait::notify_all) calls from any thread, as long as
+/// they use the platform backend. Like [`AtomicWait::wait`](crate::AtomicWait::wait), it may
+/// also complete spuriously.
+///
+/// `atomic` must remain valid until the submission completes.
+pub fn wait32(atomic: &AtomicU32, value: u32) -> Entry {
+    opcode::FutexWait::new(
+        atomic.as_ptr(),
+        value as u64,
+        FUTEX_BITSET_MATCH_ANY,
+        FUTEX2_SIZE_U32 | FUTEX2_PRIVATE,
+    )
+    .build()
+}
+
+/// Creates a submission that wakes every thread or submission waiting on `atomic`.
+///
+/// `atomic` must remain valid until the submission completes.
+pub fn notify_all32(atomic: &AtomicU32) -> Entry {
+    futex_wake(atomic, i32::MAX as u32)
+}
+
+/// Creates a submission that wakes one thread or submission waiting on `atomic`.
+///
+/// `atomic` must remain valid until the submission completes.
+pub fn notify_one32(atomic: &AtomicU32) -> Entry {
+    futex_wake(atomic, 1)
+}
+
+/// Creates a submission that completes once `atomic` is notified,
+/// or returns `None` if it does not hold `value`.
+///
+/// Since futexes are only 32 bits wide, the submission waits on the same sequence
+/// counter as blocking 64-bit waits, so it may also be woken by notifies for other
+/// atomics. The counter is static, so it always remains valid.
+pub fn wait64(atomic: &AtomicU64, value: u64) -> Option<Entry> {
+    let bucket = bucket_for_ptr(atomic as *const _ as *const _);
+    let sequence = bucket.load(Ordering::Acquire);
+    (atomic.load(Ordering::Acquire) == value).then(|| wait32(bucket, sequence))
+}
+
+/// Creates a submission that wakes every thread or submission waiting on `atomic`.
+///
+/// Since waiters on other atomics may share the sequence counter of `atomic`,
+/// 64-bit waiters cannot be woken individually.
+pub fn notify_all64(atomic: &AtomicU64) -> Entry {
+    let bucket = bucket_for_ptr(atomic as *const _ as *const _);
+    bucket.fetch_add(1, Ordering::Release);
+    notify_all32(bucket)
+}
+
+/// Creates a submission that wakes every thread or submission waiting on `atomic`,
+/// like [`notify_all64`].
+pub fn notify_one64(atomic: &AtomicU64) -> Entry {
+    notify_all64(atomic)
+}
+
+/// Describes one of the atomics in a [`waitv`] submission,
+/// which waits until `atomic` is notified if it holds `value`.
+pub fn waiter32(atomic: &AtomicU32, value: u32) -> FutexWaitV {
+    FutexWaitV::new()
+        .uaddr(atomic.as_ptr() as u64)
+        .val(value as u64)
+        .flags(FUTEX2_SIZE_U32 | FUTEX2_PRIVATE)
+}
+
+/// Creates a submission that completes once any of the `waiters` is notified, or immediately with
+/// `-EAGAIN` if any of their atomics does not hold the expected value. On wakeup, the result is the
+/// index of the waiter that was notified.
+///
+/// `waiters`, and the atomics that they describe, must remain valid until the submission completes.
+pub fn waitv(waiters: &[FutexWaitV]) -> Entry {
+    opcode::FutexWaitV::new(waiters.as_ptr(), waiters.len() as u32).build()
+}
+
+/// Creates a submission that wakes up to `count` waiters on `atomic`.
+fn futex_wake(atomic: &AtomicU32, count: u32) -> Entry {
+    opcode::FutexWake::new(
+        atomic.as_ptr(),
+        count as u64,
+        FUTEX_BITSET_MATCH_ANY,
+        FUTEX2_SIZE_U32 | FUTEX2_PRIVATE,
+    )
+    .build()
+}
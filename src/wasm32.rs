@@ -4,7 +4,11 @@ use std::{
     time::Duration,
 };
 
-use crate::private::AtomicWaitImpl;
+use crate::backend::WaitBackend;
+
+/// Waits using the `memory.atomic.wait` and `memory.atomic.notify` instructions,
+/// or the fallback table on stable compilers.
+pub struct Platform;
 
 /// Whether this thread is allowed to block and use synchronization primitives.
 #[inline(always)]
@@ -17,14 +21,12 @@ fn can_block() -> bool {
 }
 
 #[cfg(not(nightly))]
-impl AtomicWaitImpl for AtomicU32 {
-    type AtomicInner = u32;
-
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+unsafe impl WaitBackend for Platform {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, timeout: Option<Duration>) {
         if can_block() {
-            crate::condvar_table::wait(
-                self as *const _ as *const _,
-                || self.load(std::sync::atomic::Ordering::Acquire) == value,
+            crate::wait_table::wait(
+                atomic as *const _ as *const _,
+                || atomic.load(std::sync::atomic::Ordering::Acquire) == value,
                 timeout,
             );
         } else {
@@ -32,24 +34,19 @@ impl AtomicWaitImpl for AtomicU32 {
         }
     }
 
-    fn notify_all(&self) {
-        crate::condvar_table::notify_all(self as *const _ as *const _);
+    fn notify_all32(&self, atomic: &AtomicU32) {
+        crate::wait_table::notify_all(atomic as *const _ as *const _);
     }
 
-    fn notify_one(&self) {
-        crate::condvar_table::notify_one(self as *const _ as *const _);
+    fn notify_one32(&self, atomic: &AtomicU32) {
+        crate::wait_table::notify_one(atomic as *const _ as *const _);
     }
-}
 
-#[cfg(not(nightly))]
-impl AtomicWaitImpl for AtomicU64 {
-    type AtomicInner = u64;
-
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+    fn wait64(&self, atomic: &AtomicU64, value: u64, timeout: Option<Duration>) {
         if can_block() {
-            crate::condvar_table::wait(
-                self as *const _ as *const _,
-                || self.load(std::sync::atomic::Ordering::Acquire) == value,
+            crate::wait_table::wait(
+                atomic as *const _ as *const _,
+                || atomic.load(std::sync::atomic::Ordering::Acquire) == value,
                 timeout,
             );
         } else {
@@ -57,24 +54,22 @@ impl AtomicWaitImpl for AtomicU64 {
         }
     }
 
-    fn notify_all(&self) {
-        crate::condvar_table::notify_all(self as *const _ as *const _);
+    fn notify_all64(&self, atomic: &AtomicU64) {
+        crate::wait_table::notify_all(atomic as *const _ as *const _);
     }
 
-    fn notify_one(&self) {
-        crate::condvar_table::notify_one(self as *const _ as *const _);
+    fn notify_one64(&self, atomic: &AtomicU64) {
+        crate::wait_table::notify_one(atomic as *const _ as *const _);
     }
 }
 
 #[cfg(nightly)]
-impl AtomicWaitImpl for AtomicU32 {
-    type AtomicInner = u32;
-
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+unsafe impl WaitBackend for Platform {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, timeout: Option<Duration>) {
         unsafe {
             if can_block() {
                 std::arch::wasm32::memory_atomic_wait32(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     value as i32,
                     timeout
                         .map(|x| x.as_nanos().min(i64::MAX as u128) as i64)
@@ -86,28 +81,23 @@ impl AtomicWaitImpl for AtomicU32 {
         }
     }
 
-    fn notify_all(&self) {
+    fn notify_all32(&self, atomic: &AtomicU32) {
         unsafe {
-            std::arch::wasm32::memory_atomic_notify(self as *const _ as *mut _, u32::MAX);
+            std::arch::wasm32::memory_atomic_notify(atomic as *const _ as *mut _, u32::MAX);
         };
     }
 
-    fn notify_one(&self) {
+    fn notify_one32(&self, atomic: &AtomicU32) {
         unsafe {
-            std::arch::wasm32::memory_atomic_notify(self as *const _ as *mut _, 1);
+            std::arch::wasm32::memory_atomic_notify(atomic as *const _ as *mut _, 1);
         };
     }
-}
-
-#[cfg(nightly)]
-impl AtomicWaitImpl for AtomicU64 {
-    type AtomicInner = u64;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+    fn wait64(&self, atomic: &AtomicU64, value: u64, timeout: Option<Duration>) {
         unsafe {
             if can_block() {
                 std::arch::wasm32::memory_atomic_wait64(
-                    self as *const _ as *mut _,
+                    atomic as *const _ as *mut _,
                     value as i64,
                     timeout
                         .map(|x| x.as_nanos().min(i64::MAX as u128) as i64)
@@ -119,15 +109,19 @@ impl AtomicWaitImpl for AtomicU64 {
         }
     }
 
-    fn notify_all(&self) {
+    fn notify_all64(&self, atomic: &AtomicU64) {
         unsafe {
-            std::arch::wasm32::memory_atomic_notify(self as *const _ as *mut _, u32::MAX);
+            std::arch::wasm32::memory_atomic_notify(atomic as *const _ as *mut _, u32::MAX);
         };
     }
 
-    fn notify_one(&self) {
+    fn notify_one64(&self, atomic: &AtomicU64) {
         unsafe {
-            std::arch::wasm32::memory_atomic_notify(self as *const _ as *mut _, 1);
+            std::arch::wasm32::memory_atomic_notify(atomic as *const _ as *mut _, 1);
         };
     }
+
+    fn notify_is_signal_safe(&self) -> bool {
+        true
+    }
 }
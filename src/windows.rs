@@ -1,4 +1,4 @@
-use std::{
+use core::{
     sync::atomic::{AtomicU32, AtomicU64},
     time::Duration,
 };
@@ -6,60 +6,63 @@ use windows_sys::Win32::System::Threading::{
     INFINITE, WaitOnAddress, WakeByAddressAll, WakeByAddressSingle,
 };
 
-use crate::private::AtomicWaitImpl;
+use crate::backend::WaitBackend;
 
-impl AtomicWaitImpl for AtomicU32 {
-    type AtomicInner = u32;
+/// Waits using the `WaitOnAddress` and `WakeByAddress` APIs.
+pub struct Platform;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+unsafe impl WaitBackend for Platform {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, timeout: Option<Duration>) {
         unsafe {
             WaitOnAddress(
-                self as *const _ as *const _,
+                atomic as *const _ as *const _,
                 &value as *const _ as *const _,
-                size_of::<Self>(),
-                timeout
-                    .map(|x| {
-                        // Clamp to a finite u32 millisecond timeout. INFINITE (0xFFFFFFFF)
-                        // means no timeout, so avoid ever passing that when a timeout is set.
-                        let ms = x.as_millis();
-                        let capped = ms.min(u32::MAX as u128 - 1);
-                        capped as u32
-                    })
-                    .unwrap_or(INFINITE),
+                size_of::<AtomicU32>(),
+                timeout_ms(timeout),
             );
         }
     }
 
-    fn notify_all(&self) {
-        unsafe { WakeByAddressAll(self as *const _ as *const _) };
+    fn notify_all32(&self, atomic: &AtomicU32) {
+        unsafe { WakeByAddressAll(atomic as *const _ as *const _) };
     }
 
-    fn notify_one(&self) {
-        unsafe { WakeByAddressSingle(self as *const _ as *const _) };
+    fn notify_one32(&self, atomic: &AtomicU32) {
+        unsafe { WakeByAddressSingle(atomic as *const _ as *const _) };
     }
-}
-
-impl AtomicWaitImpl for AtomicU64 {
-    type AtomicInner = u64;
 
-    fn wait_timeout(&self, value: Self::AtomicInner, timeout: Option<Duration>) {
+    fn wait64(&self, atomic: &AtomicU64, value: u64, timeout: Option<Duration>) {
         unsafe {
             WaitOnAddress(
-                self as *const _ as *const _,
+                atomic as *const _ as *const _,
                 &value as *const _ as *const _,
-                size_of::<Self>(),
-                timeout
-                    .map(|x| x.as_millis().min(u32::MAX as u128 - 1) as u32)
-                    .unwrap_or(INFINITE),
+                size_of::<AtomicU64>(),
+                timeout_ms(timeout),
             );
         }
     }
 
-    fn notify_all(&self) {
-        unsafe { WakeByAddressAll(self as *const _ as *const _) };
+    fn notify_all64(&self, atomic: &AtomicU64) {
+        unsafe { WakeByAddressAll(atomic as *const _ as *const _) };
+    }
+
+    fn notify_one64(&self, atomic: &AtomicU64) {
+        unsafe { WakeByAddressSingle(atomic as *const _ as *const _) };
     }
 
-    fn notify_one(&self) {
-        unsafe { WakeByAddressSingle(self as *const _ as *const _) };
+    fn notify_is_signal_safe(&self) -> bool {
+        true
     }
 }
+
+/// Converts `timeout` to whole milliseconds for `WaitOnAddress`.
+fn timeout_ms(timeout: Option<Duration>) -> u32 {
+    timeout
+        .map(|x| {
+            // Round up, so that short timeouts sleep rather than returning immediately, and
+            // clamp to a finite value, since INFINITE (0xFFFFFFFF) means no timeout.
+            let ms = x.as_nanos().div_ceil(1_000_000);
+            ms.min(u32::MAX as u128 - 1) as u32
+        })
+        .unwrap_or(INFINITE)
+}
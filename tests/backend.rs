@@ -0,0 +1,37 @@
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, Ordering::Relaxed},
+    thread::sleep,
+    time::Duration,
+};
+use wait_on_address::{
+    AtomicWait,
+    backend::{Backend, backend, set_backend},
+};
+
+#[test]
+fn set_backend_before_use() {
+    assert_eq!(set_backend(Backend::Fallback), Ok(()));
+    assert_eq!(backend(), Backend::Fallback);
+
+    let a = AtomicU32::new(0);
+    let b = AtomicU64::new(0);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            sleep(Duration::from_millis(50));
+            a.store(1, Relaxed);
+            a.notify_all();
+            b.store(1, Relaxed);
+            b.notify_one();
+        });
+        while a.load(Relaxed) == 0 {
+            a.wait(0);
+        }
+        while b.load(Relaxed) == 0 {
+            b.wait(0);
+        }
+    });
+
+    // The backend cannot change once waits have taken place.
+    assert_eq!(set_backend(Backend::Fallback), Ok(()));
+    assert_eq!(set_backend(Backend::Platform), Err(Backend::Fallback));
+}
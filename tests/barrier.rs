@@ -0,0 +1,137 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::Duration,
+};
+use wait_on_address::sync::Barrier;
+
+#[test]
+fn barrier_single_party() {
+    let b = Barrier::new(1);
+    assert!(b.wait().unwrap().is_leader());
+    assert!(b.wait().unwrap().is_leader());
+    assert!(Barrier::new(0).wait().unwrap().is_leader());
+}
+
+#[test]
+fn barrier_phases() {
+    let threads = 8;
+    let b = Barrier::new(threads);
+    let leaders = AtomicU32::new(0);
+    let arrived = AtomicU32::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for phase in 0..100 {
+                    arrived.fetch_add(1, Ordering::Relaxed);
+                    if b.wait().unwrap().is_leader() {
+                        leaders.fetch_add(1, Ordering::Relaxed);
+                    }
+
+                    // Every thread must have arrived in this phase before any leaves it.
+                    assert!(arrived.load(Ordering::Relaxed) >= (phase + 1) * threads);
+                }
+            });
+        }
+    });
+
+    assert_eq!(leaders.into_inner(), 100);
+}
+
+#[test]
+fn barrier_register_deregister() {
+    let b = Barrier::new(2);
+    b.register();
+    assert_eq!(b.parties(), 3);
+    thread::scope(|s| {
+        let a = s.spawn(|| b.wait().unwrap().is_leader());
+        let c = s.spawn(|| b.wait().unwrap().is_leader());
+        thread::sleep(Duration::from_millis(20));
+        assert!(!a.is_finished() && !c.is_finished());
+
+        // The third party leaves instead of arriving, which releases the others.
+        assert!(b.deregister());
+        assert!(!a.join().unwrap());
+        assert!(!c.join().unwrap());
+    });
+
+    assert_eq!(b.parties(), 2);
+    assert!(!b.deregister());
+    assert!(b.wait().unwrap().is_leader());
+}
+
+#[test]
+#[should_panic(expected = "without parties")]
+fn barrier_deregister_empty() {
+    Barrier::new(0).deregister();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn barrier_timeout_breaks() {
+    use std::time::Instant;
+    use wait_on_address::sync::BarrierWaitError;
+
+    let b = Barrier::new(3);
+    thread::scope(|s| {
+        let waiter = s.spawn(|| b.wait());
+        thread::sleep(Duration::from_millis(20));
+
+        let t = Instant::now();
+        assert_eq!(
+            b.wait_timeout(Duration::from_millis(50)),
+            Err(BarrierWaitError::TimedOut)
+        );
+        assert!(t.elapsed() >= Duration::from_millis(50));
+        assert_eq!(waiter.join().unwrap(), Err(BarrierWaitError::Broken));
+    });
+
+    assert!(b.is_broken());
+    assert_eq!(b.wait(), Err(BarrierWaitError::Broken));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn barrier_timeout_completes() {
+    use std::time::Instant;
+
+    let b = Barrier::new(2);
+    thread::scope(|s| {
+        s.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            b.wait().unwrap();
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        assert!(b.wait_until(deadline).is_ok());
+    });
+
+    assert!(!b.is_broken());
+}
+
+#[test]
+fn stress_barrier_dynamic_parties() {
+    let threads = 16;
+    let b = Barrier::new(threads);
+    let leaders = AtomicU32::new(0);
+    thread::scope(|s| {
+        for i in 0..threads {
+            let (b, leaders) = (&b, &leaders);
+            s.spawn(move || {
+                // Threads leave the barrier after different numbers of phases.
+                for _ in 0..(i + 1) * 20 {
+                    if b.wait().unwrap().is_leader() {
+                        leaders.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+
+                if b.deregister() {
+                    leaders.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    assert_eq!(b.parties(), 0);
+    assert_eq!(leaders.into_inner(), threads * 20);
+}
@@ -0,0 +1,55 @@
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering::Relaxed},
+    thread::yield_now,
+    time::Duration,
+};
+use wait_on_address::backend::WaitBackend;
+
+/// A backend that yields instead of sleeping, like a coroutine
+/// scheduler switching to another task, and counts each operation.
+pub struct CountingBackend {
+    pub waits: AtomicUsize,
+    pub notifies: AtomicUsize,
+}
+
+impl CountingBackend {
+    /// Creates a backend that has not yet seen any operations.
+    pub const fn new() -> Self {
+        Self {
+            waits: AtomicUsize::new(0),
+            notifies: AtomicUsize::new(0),
+        }
+    }
+}
+
+unsafe impl WaitBackend for CountingBackend {
+    fn wait32(&self, atomic: &AtomicU32, value: u32, _: Option<Duration>) {
+        self.waits.fetch_add(1, Relaxed);
+        if atomic.load(Relaxed) == value {
+            yield_now();
+        }
+    }
+
+    fn notify_all32(&self, _: &AtomicU32) {
+        self.notifies.fetch_add(1, Relaxed);
+    }
+
+    fn notify_one32(&self, _: &AtomicU32) {
+        self.notifies.fetch_add(1, Relaxed);
+    }
+
+    fn wait64(&self, atomic: &AtomicU64, value: u64, _: Option<Duration>) {
+        self.waits.fetch_add(1, Relaxed);
+        if atomic.load(Relaxed) == value {
+            yield_now();
+        }
+    }
+
+    fn notify_all64(&self, _: &AtomicU64) {
+        self.notifies.fetch_add(1, Relaxed);
+    }
+
+    fn notify_one64(&self, _: &AtomicU64) {
+        self.notifies.fetch_add(1, Relaxed);
+    }
+}
@@ -0,0 +1,241 @@
+use std::{thread, time::Duration};
+use wait_on_address::sync::{Condvar, Mutex};
+
+#[test]
+fn condvar_notify_one() {
+    let m = Mutex::new(false);
+    let c = Condvar::new();
+    thread::scope(|s| {
+        s.spawn(|| {
+            let mut ready = m.lock();
+            while !*ready {
+                ready = c.wait(ready);
+            }
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        *m.lock() = true;
+        c.notify_one();
+    });
+}
+
+#[test]
+fn condvar_notify_all() {
+    let m = Mutex::new((false, 0));
+    let c = Condvar::new();
+    let threads = 16;
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                let mut state = c.wait_while(m.lock(), |(ready, _)| !*ready);
+                state.1 += 1;
+            });
+        }
+
+        thread::sleep(Duration::from_millis(20));
+        m.lock().0 = true;
+        c.notify_all();
+    });
+
+    assert_eq!(m.into_inner().1, threads);
+}
+
+#[test]
+fn condvar_notify_all_unlocked_mutex() {
+    // Waiters that are requeued onto the mutex must still
+    // all be woken when the notifier does not hold it.
+    let m = Mutex::new(0);
+    let c = Condvar::new();
+    let threads = 16;
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                let mut state = m.lock();
+                *state += 1;
+                let mut state = c.wait_while(state, |x| *x <= threads);
+                *state += 1;
+            });
+        }
+
+        while *m.lock() < threads {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        *m.lock() += 1;
+        c.notify_all();
+    });
+
+    assert_eq!(m.into_inner(), threads * 2 + 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn condvar_wait_timeout() {
+    use std::time::Instant;
+
+    let m = Mutex::new(false);
+    let c = Condvar::new();
+    let t = Instant::now();
+    let (guard, result) = c.wait_timeout(m.lock(), Duration::from_millis(50));
+    assert!(result.timed_out());
+    assert!(t.elapsed() >= Duration::from_millis(50));
+    drop(guard);
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            let mut ready = m.lock();
+            while !*ready {
+                let (guard, result) = c.wait_until(ready, deadline);
+                assert!(!result.timed_out());
+                ready = guard;
+            }
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        *m.lock() = true;
+        c.notify_all();
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "more than one mutex")]
+fn condvar_two_mutexes() {
+    let a = Mutex::new(());
+    let b = Mutex::new(());
+    let c = Condvar::new();
+    thread::scope(|s| {
+        s.spawn(|| drop(c.wait_timeout(a.lock(), Duration::from_millis(200))));
+
+        // Another mutex may not be used while a thread is still waiting.
+        thread::sleep(Duration::from_millis(50));
+        drop(c.wait_timeout(b.lock(), Duration::from_millis(1)));
+    });
+}
+
+#[test]
+fn stress_condvar_producer_consumer() {
+    let m = Mutex::new(Vec::new());
+    let c = Condvar::new();
+    let producers = 8;
+    let items = 1_000;
+    let consumed = Mutex::new(0);
+    thread::scope(|s| {
+        for _ in 0..producers {
+            s.spawn(|| {
+                for i in 0..items {
+                    m.lock().push(i);
+                    if i % 2 == 0 {
+                        c.notify_one();
+                    } else {
+                        c.notify_all();
+                    }
+                }
+            });
+        }
+
+        for _ in 0..producers {
+            s.spawn(|| {
+                for _ in 0..items {
+                    let mut queue = c.wait_while(m.lock(), |x| x.is_empty());
+                    queue.pop().unwrap();
+                    *consumed.lock() += 1;
+                }
+            });
+        }
+    });
+
+    assert_eq!(consumed.into_inner(), producers * items);
+}
+
+#[cfg(feature = "lock_api")]
+#[test]
+fn condvar_lock_api() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// A minimal spinlock, which stands in for any `lock_api` mutex.
+    struct RawSpinlock(AtomicBool);
+
+    unsafe impl lock_api::RawMutex for RawSpinlock {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Self(AtomicBool::new(false));
+
+        type GuardMarker = lock_api::GuardSend;
+
+        fn lock(&self) {
+            while !self.try_lock() {
+                std::hint::spin_loop();
+            }
+        }
+
+        fn try_lock(&self) -> bool {
+            !self.0.swap(true, Ordering::Acquire)
+        }
+
+        unsafe fn unlock(&self) {
+            self.0.store(false, Ordering::Release);
+        }
+    }
+
+    let m = lock_api::Mutex::<RawSpinlock, _>::new(0);
+    let c = Condvar::new();
+    let threads = 8;
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                let mut state = c.wait_while(m.lock(), |x| *x == 0);
+                *state += 1;
+            });
+        }
+
+        thread::sleep(Duration::from_millis(20));
+        *m.lock() = 1;
+        c.notify_all();
+    });
+
+    assert_eq!(m.into_inner(), threads + 1);
+
+    #[cfg(feature = "std")]
+    {
+        let m = lock_api::Mutex::<RawSpinlock, _>::new(());
+        let c = Condvar::new();
+        let (_guard, result) = c.wait_timeout(m.lock(), Duration::from_millis(10));
+        assert!(result.timed_out());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn condvar_wait_after_move() {
+    struct Pair {
+        m: Mutex<bool>,
+        c: Condvar,
+    }
+
+    fn wait_once(pair: &Pair) {
+        let guard = pair.m.lock();
+        drop(pair.c.wait_timeout(guard, Duration::from_millis(1)));
+    }
+
+    let pair = Pair {
+        m: Mutex::new(false),
+        c: Condvar::new(),
+    };
+    wait_once(&pair);
+
+    // Moving the pair changes the address of the mutex, which
+    // the condition variable must not remember after the wait.
+    let pair = Box::new(pair);
+    wait_once(&pair);
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| drop(pair.c.wait_while(pair.m.lock(), |ready| !*ready)));
+        }
+
+        thread::sleep(Duration::from_millis(20));
+        *pair.m.lock() = true;
+        pair.c.notify_all();
+    });
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::CountingBackend;
+use std::{
+    sync::atomic::{AtomicI64, AtomicU32, Ordering::Relaxed},
+    time::Duration,
+};
+use wait_on_address::{
+    AtomicWait,
+    backend::{Backend, backend, set_backend},
+};
+
+static COUNTING: CountingBackend = CountingBackend::new();
+
+#[test]
+fn custom_backend_receives_operations() {
+    if cfg!(feature = "force-fallback") {
+        assert_eq!(
+            set_backend(Backend::Custom(&COUNTING)),
+            Err(Backend::Fallback)
+        );
+        return;
+    }
+
+    assert_eq!(set_backend(Backend::Custom(&COUNTING)), Ok(()));
+    assert_eq!(backend(), Backend::Custom(&COUNTING));
+    assert_eq!(
+        set_backend(Backend::Platform),
+        Err(Backend::Custom(&COUNTING))
+    );
+
+    let a = AtomicU32::new(0);
+    a.wait(1);
+    a.wait_timeout(0, Duration::from_secs(60));
+    a.notify_one();
+
+    let b = AtomicI64::new(-1);
+    b.wait(-1);
+    b.notify_all();
+
+    assert_eq!(COUNTING.waits.load(Relaxed), 3);
+    assert_eq!(COUNTING.notifies.load(Relaxed), 2);
+}
@@ -0,0 +1,155 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::Duration,
+};
+use wait_on_address::sync::{AutoResetEvent, ManualResetEvent};
+
+#[test]
+fn manual_reset_event_set_reset() {
+    let e = ManualResetEvent::new(false);
+    assert!(!e.is_set());
+    e.set();
+    assert!(e.is_set());
+
+    // A set event releases every wait until it is reset.
+    e.wait();
+    e.wait();
+    e.reset();
+    assert!(!e.is_set());
+    assert!(ManualResetEvent::new(true).is_set());
+}
+
+#[test]
+fn manual_reset_event_blocks_until_set() {
+    let e = ManualResetEvent::default();
+    thread::scope(|s| {
+        let waiter = s.spawn(|| e.wait());
+        thread::sleep(Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+        e.set();
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn manual_reset_event_set_then_reset_releases_waiters() {
+    let e = ManualResetEvent::new(false);
+    thread::scope(|s| {
+        let waiters: Vec<_> = (0..8)
+            .map(|_| s.spawn(|| e.wait_timeout(Duration::from_secs(5))))
+            .collect();
+
+        thread::sleep(Duration::from_millis(50));
+        e.set();
+        e.reset();
+
+        // Every thread that was waiting during the set returns, even though it was reset.
+        for waiter in waiters {
+            assert!(waiter.join().unwrap());
+        }
+    });
+    assert!(!e.is_set());
+}
+
+#[test]
+fn auto_reset_event_consumes_signal() {
+    let e = AutoResetEvent::new(true);
+    assert!(e.is_set());
+    e.wait();
+    assert!(!e.is_set());
+    assert!(!e.try_wait());
+
+    // Setting an event that is already set has no effect.
+    e.set();
+    e.set();
+    assert!(e.try_wait());
+    assert!(!e.try_wait());
+
+    e.set();
+    e.reset();
+    assert!(!e.try_wait());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn event_wait_timeout() {
+    use std::time::Instant;
+
+    let manual = ManualResetEvent::new(false);
+    let auto = AutoResetEvent::new(false);
+    let t = Instant::now();
+    assert!(!manual.wait_timeout(Duration::from_millis(50)));
+    assert!(!auto.wait_timeout(Duration::from_millis(50)));
+    assert!(t.elapsed() >= Duration::from_millis(100));
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            assert!(manual.wait_until(deadline));
+            assert!(auto.wait_until(deadline));
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        manual.set();
+        auto.set();
+    });
+
+    assert!(manual.is_set());
+    assert!(!auto.is_set());
+}
+
+#[test]
+fn stress_manual_reset_event_many_waiters() {
+    let e = ManualResetEvent::new(false);
+    let woke = AtomicU32::new(0);
+
+    let threads = 64;
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                e.wait();
+                woke.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        // Give threads time to start waiting
+        thread::sleep(Duration::from_millis(50));
+        e.set();
+    });
+
+    assert_eq!(woke.load(Ordering::Relaxed), threads);
+    assert!(e.is_set());
+}
+
+#[test]
+fn stress_auto_reset_event_many_waiters() {
+    let e = AutoResetEvent::new(false);
+    let woke = AtomicU32::new(0);
+
+    let threads = 64;
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                e.wait();
+                woke.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        // Give threads time to start waiting
+        thread::sleep(Duration::from_millis(50));
+        for i in 1..=threads {
+            e.set();
+            while woke.load(Ordering::Relaxed) < i {
+                thread::yield_now();
+            }
+
+            // Each set releases exactly one waiter.
+            thread::sleep(Duration::from_millis(1));
+            assert_eq!(woke.load(Ordering::Relaxed), i);
+        }
+    });
+
+    assert_eq!(woke.load(Ordering::Relaxed), threads);
+    assert!(!e.is_set());
+}
@@ -0,0 +1,158 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::Duration,
+};
+use wait_on_address::sync::EventFlags;
+
+#[test]
+fn event_flags_set_clear() {
+    let f = EventFlags::new(0b1);
+    assert_eq!(f.set(0b110), 0b1);
+    assert_eq!(f.get(), 0b111);
+    assert_eq!(f.clear(0b11), 0b111);
+    assert_eq!(f.get(), 0b100);
+}
+
+#[test]
+fn event_flags_ready_without_blocking() {
+    let f = EventFlags::new(0b1010);
+    assert_eq!(f.wait_any(0b0011, false), 0b1010);
+    assert_eq!(f.wait_all(0b1010, false), 0b1010);
+    assert_eq!(f.wait_all(0, false), 0b1010);
+
+    // Clearing only clears the flags in the mask.
+    assert_eq!(f.wait_any(0b0111, true), 0b1010);
+    assert_eq!(f.get(), 0b1000);
+    assert_eq!(f.wait_all(0b1000, true), 0b1000);
+    assert_eq!(f.get(), 0);
+}
+
+#[test]
+fn event_flags_wait_any_ignores_other_flags() {
+    let f = EventFlags::default();
+    thread::scope(|s| {
+        let waiter = s.spawn(|| f.wait_any(1 << 7, true));
+        thread::sleep(Duration::from_millis(20));
+        f.set(1 << 3);
+        thread::sleep(Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+
+        f.set(1 << 7);
+        assert_eq!(waiter.join().unwrap(), 1 << 3 | 1 << 7);
+    });
+
+    assert_eq!(f.get(), 1 << 3);
+}
+
+#[test]
+fn event_flags_wait_all_needs_every_flag() {
+    let f = EventFlags::default();
+    thread::scope(|s| {
+        let waiter = s.spawn(|| f.wait_all(0b11, true));
+        thread::sleep(Duration::from_millis(20));
+        f.set(0b01);
+        thread::sleep(Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+
+        f.set(0b110);
+        assert_eq!(waiter.join().unwrap(), 0b111);
+    });
+
+    assert_eq!(f.get(), 0b100);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn event_flags_wait_timeout() {
+    use std::time::Instant;
+
+    let f = EventFlags::new(0b1);
+    let t = Instant::now();
+    assert_eq!(
+        f.wait_any_timeout(0b10, true, Duration::from_millis(50)),
+        None
+    );
+    assert_eq!(
+        f.wait_all_timeout(0b11, true, Duration::from_millis(50)),
+        None
+    );
+    assert!(t.elapsed() >= Duration::from_millis(100));
+    assert_eq!(f.get(), 0b1);
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            assert_eq!(f.wait_all_until(0b11, false, deadline), Some(0b11));
+            assert_eq!(f.wait_any_until(0b10, true, deadline), Some(0b11));
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        f.set(0b10);
+    });
+
+    assert_eq!(f.get(), 0b1);
+}
+
+#[test]
+fn stress_event_flags_many_waiters() {
+    let f = EventFlags::default();
+    let woke = AtomicU32::new(0);
+
+    let threads = 64;
+    thread::scope(|s| {
+        for i in 0..threads {
+            let (f, woke) = (&f, &woke);
+            s.spawn(move || {
+                // Each thread waits on its own pair of flags.
+                let mask = 1 << (i % 32) | 1 << ((i + 1) % 32);
+                if i % 2 == 0 {
+                    f.wait_any(mask, false);
+                } else {
+                    f.wait_all(mask, false);
+                }
+
+                woke.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        // Give threads time to start waiting
+        thread::sleep(Duration::from_millis(50));
+        for bit in 0..32 {
+            f.set(1 << bit);
+        }
+    });
+
+    assert_eq!(woke.load(Ordering::Relaxed), threads);
+    assert_eq!(f.get(), u32::MAX);
+}
+
+#[test]
+fn stress_event_flags_clear_on_exit() {
+    let f = EventFlags::default();
+    let taken = AtomicU32::new(0);
+    thread::scope(|s| {
+        for i in 0..8 {
+            let (f, taken) = (&f, &taken);
+            s.spawn(move || {
+                for _ in 0..500 {
+                    f.wait_any(1 << i, true);
+                    taken.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+
+        // Each flag is consumed by its waiter before it is set again.
+        for _ in 0..500 {
+            for i in 0..8 {
+                while f.get() & 1 << i != 0 {
+                    thread::yield_now();
+                }
+
+                f.set(1 << i);
+            }
+        }
+    });
+
+    assert_eq!(taken.load(Ordering::Relaxed), 8 * 500);
+}
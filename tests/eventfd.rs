@@ -0,0 +1,83 @@
+#![cfg(all(any(target_os = "linux", target_os = "android"), feature = "eventfd"))]
+
+use std::{
+    os::fd::AsRawFd,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering::Relaxed},
+    thread::sleep,
+    time::Duration,
+};
+use wait_on_address::{AtomicWait, eventfd::AtomicNotifier};
+
+/// Waits up to `timeout_ms` for `notifier` to become readable.
+fn poll(notifier: &AtomicNotifier, timeout_ms: i32) -> bool {
+    let mut fd = libc::pollfd {
+        fd: notifier.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    unsafe { libc::poll(&mut fd, 1, timeout_ms) == 1 }
+}
+
+#[test]
+fn notifier_signaled_by_notify() {
+    let a = AtomicU32::new(0);
+    let notifier = AtomicNotifier::new(&a).unwrap();
+    assert!(!notifier.reset());
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            while a.load(Relaxed) == 0 {
+                a.wait(0);
+            }
+        });
+
+        sleep(Duration::from_millis(20));
+        a.store(1, Relaxed);
+        a.notify_one();
+    });
+
+    assert!(poll(&notifier, 5_000));
+    assert!(notifier.reset());
+    assert!(!poll(&notifier, 0));
+}
+
+#[test]
+fn notifier_ignores_other_atomics() {
+    let a = AtomicU64::new(0);
+    let b = AtomicU64::new(0);
+    let notifier = AtomicNotifier::new(&a).unwrap();
+    let other = AtomicNotifier::new(&a).unwrap();
+
+    b.notify_all();
+    assert!(!poll(&notifier, 0));
+
+    a.notify_all();
+    assert!(poll(&notifier, 0));
+    assert!(poll(&other, 0));
+
+    drop(other);
+    notifier.reset();
+    a.notify_all();
+    assert!(notifier.reset());
+}
+
+#[test]
+fn notifier_wakes_event_loop() {
+    let a = AtomicU32::new(0);
+    let a = &a;
+
+    std::thread::scope(|s| {
+        let notifier = AtomicNotifier::new(a).unwrap();
+        s.spawn(move || {
+            while a.load(Relaxed) < 100 {
+                assert!(poll(&notifier, 5_000));
+                notifier.reset();
+            }
+        });
+
+        for _ in 0..100 {
+            a.fetch_add(1, Relaxed);
+            a.notify_all();
+        }
+    });
+}
@@ -0,0 +1,80 @@
+#![cfg(all(unix, feature = "std"))]
+
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, Ordering::Relaxed},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use wait_on_address::{
+    AtomicWait,
+    backend::{Backend, set_backend},
+};
+
+#[test]
+fn fork_with_blocked_waiters() {
+    assert_eq!(set_backend(Backend::Fallback), Ok(()));
+
+    let a = AtomicU64::new(0);
+    let stop = AtomicBool::new(false);
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                while a.load(Relaxed) == 0 {
+                    a.wait(0);
+                }
+            });
+        }
+
+        // Keep the table entry for `a` busy, so that forks are likely to
+        // happen while another thread holds its lock.
+        s.spawn(|| {
+            while !stop.load(Relaxed) {
+                a.notify_one();
+            }
+        });
+
+        sleep(Duration::from_millis(50));
+
+        let mut failure = None;
+        for _ in 0..16 {
+            match unsafe { libc::fork() } {
+                -1 => panic!("fork failed"),
+                0 => {
+                    // The child is single-threaded after `fork`, so the reinitialized
+                    // table is safe to use. The result is reported through the exit
+                    // status, since the test harness does not run in the child.
+                    a.wait_timeout(0, Duration::from_millis(1));
+                    a.notify_all();
+                    unsafe { libc::_exit(0) };
+                }
+                child => {
+                    let t = Instant::now();
+                    let mut status = 0;
+                    while unsafe { libc::waitpid(child, &mut status, libc::WNOHANG) } == 0 {
+                        if t.elapsed() > Duration::from_secs(5) {
+                            unsafe { libc::kill(child, libc::SIGKILL) };
+                            failure = Some("child process deadlocked after fork");
+                            break;
+                        }
+                        sleep(Duration::from_millis(1));
+                    }
+                    if failure.is_none()
+                        && !(libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0)
+                    {
+                        failure = Some("child process exited abnormally");
+                    }
+                    if failure.is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        stop.store(true, Relaxed);
+        a.store(1, Relaxed);
+        a.notify_all();
+
+        assert_eq!(failure, None);
+    });
+}
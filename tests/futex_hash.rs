@@ -0,0 +1,38 @@
+#![cfg(any(target_os = "linux", target_os = "android"))]
+
+use std::sync::atomic::{AtomicU32, Ordering::Relaxed};
+use wait_on_address::{
+    AtomicWait,
+    backend::{configure_private_futex_hash, private_futex_hash},
+};
+
+#[test]
+fn configure_private_futex_hash_before_use() {
+    let result = configure_private_futex_hash(512);
+    if result.kernel {
+        assert_eq!(private_futex_hash(), Some(512));
+    } else {
+        assert!(!result.fallback);
+    }
+
+    // Waits must work with either table size, whichever backend is in use.
+    let a = AtomicU32::new(0);
+    std::thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                while a.load(Relaxed) == 0 {
+                    a.wait(0);
+                }
+            });
+        }
+
+        a.store(1, Relaxed);
+        a.notify_all();
+    });
+}
+
+#[test]
+#[should_panic]
+fn configure_private_futex_hash_rejects_odd_sizes() {
+    configure_private_futex_hash(3);
+}
@@ -0,0 +1,146 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::Duration,
+};
+use wait_on_address::sync::{Latch, WaitGroup};
+
+#[test]
+fn latch_counts_down_once() {
+    let l = Latch::new(2);
+    assert!(!l.try_wait());
+    l.count_down();
+    assert_eq!(l.count(), 1);
+    l.count_down();
+    assert!(l.try_wait());
+
+    // The latch stays released.
+    l.count_down();
+    assert_eq!(l.count(), 0);
+    l.wait();
+    Latch::new(0).wait();
+}
+
+#[test]
+fn stress_latch_many_waiters() {
+    let threads = 64;
+    let l = Latch::new(threads);
+    let woke = AtomicU32::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                l.count_down();
+                l.wait();
+                woke.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        l.wait();
+    });
+
+    assert_eq!(woke.load(Ordering::Relaxed), threads);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn latch_wait_timeout() {
+    use std::time::Instant;
+
+    let l = Latch::new(1);
+    let t = Instant::now();
+    assert!(!l.wait_timeout(Duration::from_millis(50)));
+    assert!(t.elapsed() >= Duration::from_millis(50));
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            l.count_down();
+        });
+
+        assert!(l.wait_until(Instant::now() + Duration::from_secs(10)));
+    });
+}
+
+#[test]
+fn wait_group_add_done() {
+    let wg = WaitGroup::new();
+    wg.wait();
+    wg.add(3);
+    assert_eq!(wg.count(), 3);
+    thread::scope(|s| {
+        for _ in 0..3 {
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(20));
+                wg.done();
+            });
+        }
+
+        wg.wait();
+        assert_eq!(wg.count(), 0);
+    });
+}
+
+#[test]
+#[should_panic(expected = "without outstanding tasks")]
+fn wait_group_done_without_tasks() {
+    WaitGroup::default().done();
+}
+
+#[test]
+fn wait_group_done_without_tasks_keeps_state() {
+    let wg = WaitGroup::new();
+    let result = std::panic::catch_unwind(|| wg.done());
+    assert!(result.is_err());
+
+    // The failed call must not have wrapped the counter.
+    assert_eq!(wg.count(), 0);
+    wg.wait();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wait_group_wait_timeout() {
+    use std::time::Instant;
+
+    let wg = WaitGroup::new();
+    wg.add(1);
+    let t = Instant::now();
+    assert!(!wg.wait_timeout(Duration::from_millis(50)));
+    assert!(t.elapsed() >= Duration::from_millis(50));
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            wg.done();
+        });
+
+        assert!(wg.wait_until(Instant::now() + Duration::from_secs(10)));
+    });
+}
+
+#[test]
+fn stress_wait_group_reuse() {
+    let wg = WaitGroup::new();
+    let finished = AtomicU32::new(0);
+    for round in 1..=50 {
+        wg.add(16);
+        thread::scope(|s| {
+            for _ in 0..16 {
+                s.spawn(|| {
+                    finished.fetch_add(1, Ordering::Relaxed);
+                    wg.done();
+                });
+            }
+
+            // Several threads wait for the same round.
+            for _ in 0..4 {
+                s.spawn(|| {
+                    wg.wait();
+                    assert_eq!(finished.load(Ordering::Relaxed), round * 16);
+                });
+            }
+
+            wg.wait();
+        });
+    }
+}
@@ -0,0 +1,114 @@
+use wait_on_address::sync::Mutex;
+
+#[test]
+fn mutex_lock_unlock() {
+    let m = Mutex::new(1);
+    *m.lock() += 1;
+    assert_eq!(*m.try_lock().unwrap(), 2);
+    assert_eq!(m.into_inner(), 2);
+}
+
+#[test]
+fn mutex_try_lock() {
+    let m = Mutex::new(());
+    let guard = m.lock();
+    std::thread::scope(|s| {
+        s.spawn(|| assert!(m.try_lock().is_none()));
+    });
+    drop(guard);
+    assert!(m.try_lock().is_some());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn mutex_try_lock_for() {
+    use std::time::{Duration, Instant};
+
+    let m = Mutex::new(0);
+    let guard = m.lock();
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let t = Instant::now();
+            assert!(m.try_lock_for(Duration::from_millis(50)).is_none());
+            assert!(t.elapsed() >= Duration::from_millis(50));
+        });
+    });
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            assert_eq!(*m.try_lock_for(Duration::from_secs(10)).unwrap(), 1);
+        });
+
+        std::thread::sleep(Duration::from_millis(20));
+        let mut guard = guard;
+        *guard = 1;
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn mutex_poisoning() {
+    let m = Mutex::new(0);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let _guard = m.lock();
+            panic!("poison the mutex");
+        })
+        .join()
+        .unwrap_err();
+    });
+
+    assert!(m.is_poisoned());
+    assert_eq!(*m.lock(), 0);
+    *m.lock_checked().unwrap_err().into_inner() = 1;
+    m.clear_poison();
+    assert_eq!(*m.lock_checked().unwrap(), 1);
+}
+
+#[test]
+fn stress_mutex_many_threads() {
+    let m = Mutex::new(0u64);
+    let threads = 64;
+    std::thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..1_000 {
+                    *m.lock() += 1;
+                }
+            });
+        }
+    });
+
+    assert_eq!(m.into_inner(), threads * 1_000);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stress_mutex_timed_and_blocking() {
+    use std::time::Duration;
+
+    let m = Mutex::new(0u64);
+    let threads = 16;
+    std::thread::scope(|s| {
+        for i in 0..threads {
+            let m = &m;
+            s.spawn(move || {
+                let mut done = 0;
+                while done < 1_000 {
+                    let guard = if i % 2 == 0 {
+                        Some(m.lock())
+                    } else {
+                        m.try_lock_for(Duration::from_micros(10))
+                    };
+
+                    if let Some(mut guard) = guard {
+                        *guard += 1;
+                        done += 1;
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(m.into_inner(), threads * 1_000);
+}
@@ -0,0 +1,57 @@
+#![cfg(any(target_os = "linux", target_os = "android"))]
+
+use std::{
+    sync::atomic::Ordering::Relaxed,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use wait_on_address::{
+    AtomicWait,
+    backend::{Backend, backend},
+    numa::{NumaAtomicU32, is_supported},
+};
+
+#[test]
+fn numa_wait_woken_by_notify() {
+    let a = NumaAtomicU32::new(0);
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                while a.load(Relaxed) == 0 {
+                    a.wait(0);
+                }
+            });
+        }
+
+        sleep(Duration::from_millis(20));
+        a.store(1, Relaxed);
+        a.notify_all();
+    });
+
+    if is_supported() && backend() == Backend::Platform {
+        assert!(a.node().is_some());
+    }
+}
+
+#[test]
+fn numa_wait_timeout() {
+    let a = NumaAtomicU32::with_node(0, 0);
+    let t = Instant::now();
+    a.wait_timeout(0, Duration::from_millis(50));
+    // The spinning fallback returns early instead of sleeping.
+    if backend() == Backend::Platform {
+        assert!(t.elapsed() >= Duration::from_millis(50));
+    }
+    assert_eq!(a.node(), Some(0));
+}
+
+#[test]
+fn numa_set_node() {
+    let mut a = NumaAtomicU32::new(7);
+    assert_eq!(a.node(), None);
+    a.set_node(0);
+    assert_eq!(a.node(), Some(0));
+    a.notify_one();
+    assert_eq!(a.into_inner(), 7);
+}
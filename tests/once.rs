@@ -0,0 +1,195 @@
+use std::{
+    panic,
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::Duration,
+};
+use wait_on_address::sync::{Lazy, Once, OnceLock};
+
+#[test]
+fn once_runs_once() {
+    let once = Once::new();
+    let runs = AtomicU32::new(0);
+    thread::scope(|s| {
+        for _ in 0..64 {
+            s.spawn(|| {
+                once.call_once(|| {
+                    thread::sleep(Duration::from_millis(20));
+                    runs.fetch_add(1, Ordering::Relaxed);
+                });
+
+                // Every caller returns after the initializer has completed.
+                assert!(once.is_completed());
+                assert_eq!(runs.load(Ordering::Relaxed), 1);
+            });
+        }
+    });
+
+    once.wait();
+    once.call_once(|| unreachable!());
+}
+
+#[test]
+fn once_poisoning() {
+    let once = Once::new();
+    assert!(panic::catch_unwind(|| once.call_once(|| panic!())).is_err());
+    assert!(!once.is_completed());
+    assert!(panic::catch_unwind(|| once.call_once(|| {})).is_err());
+    assert!(panic::catch_unwind(|| once.wait()).is_err());
+
+    once.call_once_force(|state| assert!(state.is_poisoned()));
+    assert!(once.is_completed());
+    once.wait();
+}
+
+#[test]
+fn once_wakes_waiters_on_poison() {
+    let once = Once::new();
+    thread::scope(|s| {
+        let waiter = s.spawn(|| {
+            thread::sleep(Duration::from_millis(10));
+            panic::catch_unwind(|| once.wait()).is_err()
+        });
+
+        let forced = s.spawn(|| {
+            thread::sleep(Duration::from_millis(10));
+            once.wait_force();
+        });
+
+        let _ = panic::catch_unwind(|| {
+            once.call_once(|| {
+                thread::sleep(Duration::from_millis(50));
+                panic!();
+            })
+        });
+
+        assert!(waiter.join().unwrap());
+        thread::sleep(Duration::from_millis(20));
+        assert!(!forced.is_finished());
+        once.call_once_force(|_| {});
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn once_wait_timeout() {
+    use std::time::Instant;
+
+    let once = Once::new();
+    let t = Instant::now();
+    assert!(!once.wait_timeout(Duration::from_millis(50)));
+    assert!(t.elapsed() >= Duration::from_millis(50));
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            once.call_once(|| thread::sleep(Duration::from_millis(100)));
+        });
+
+        // A hung initializer does not block observers forever.
+        thread::sleep(Duration::from_millis(10));
+        assert!(!once.wait_timeout(Duration::from_millis(20)));
+        assert!(once.wait_until(Instant::now() + Duration::from_secs(10)));
+    });
+}
+
+#[test]
+fn once_lock_set_get() {
+    let mut cell = OnceLock::new();
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.set(1), Ok(()));
+    assert_eq!(cell.set(2), Err(2));
+    assert_eq!(cell.get_or_init(|| 3), &1);
+    *cell.get_mut().unwrap() += 1;
+    assert_eq!(cell.wait(), &2);
+    assert_eq!(cell.take(), Some(2));
+    assert_eq!(cell.get(), None);
+    assert_eq!(OnceLock::from(4).into_inner(), Some(4));
+}
+
+#[test]
+fn once_lock_retries_after_panic() {
+    let cell = OnceLock::new();
+    assert!(panic::catch_unwind(|| cell.get_or_init(|| panic!())).is_err());
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.get_or_init(|| 1), &1);
+}
+
+#[test]
+fn once_lock_drops_value() {
+    use std::sync::Arc;
+
+    let value = Arc::new(());
+    let cell = OnceLock::new();
+    cell.set(value.clone()).unwrap();
+    assert_eq!(Arc::strong_count(&value), 2);
+    drop(cell);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn stress_once_lock_many_waiters() {
+    let cell = OnceLock::new();
+    let woke = AtomicU32::new(0);
+
+    let threads = 64;
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                assert_eq!(cell.wait(), &7);
+                woke.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        // Give threads time to start waiting
+        thread::sleep(Duration::from_millis(50));
+        cell.set(7).unwrap();
+    });
+
+    assert_eq!(woke.load(Ordering::Relaxed), threads);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn once_lock_wait_timeout() {
+    use std::time::Instant;
+
+    let cell = OnceLock::new();
+    assert_eq!(cell.wait_timeout(Duration::from_millis(20)), None);
+    thread::scope(|s| {
+        s.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            cell.set(1).unwrap();
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        assert_eq!(cell.wait_until(deadline), Some(&1));
+    });
+}
+
+#[test]
+fn lazy_initializes_once() {
+    static RUNS: AtomicU32 = AtomicU32::new(0);
+    static VALUE: Lazy<u32> = Lazy::new(|| {
+        RUNS.fetch_add(1, Ordering::Relaxed);
+        thread::sleep(Duration::from_millis(20));
+        5
+    });
+
+    assert_eq!(Lazy::get(&VALUE), None);
+    thread::scope(|s| {
+        for _ in 0..16 {
+            s.spawn(|| assert_eq!(*VALUE, 5));
+        }
+    });
+
+    assert_eq!(Lazy::get(&VALUE), Some(&5));
+    assert_eq!(RUNS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn lazy_poisoning() {
+    let lazy = Lazy::new(|| -> u32 { panic!() });
+    assert!(panic::catch_unwind(|| *lazy).is_err());
+    assert!(panic::catch_unwind(|| Lazy::force(&lazy)).is_err());
+    assert_eq!(Lazy::get(&lazy), None);
+}
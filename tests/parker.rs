@@ -0,0 +1,108 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::Duration,
+};
+use wait_on_address::sync::Parker;
+
+#[test]
+fn parker_token_before_park() {
+    let p = Parker::new();
+    let u = p.unparker();
+
+    // An unpark before the park is not lost.
+    u.unpark();
+    p.park();
+
+    // Several unparks only release a single park.
+    u.unpark();
+    u.unpark();
+    p.park();
+}
+
+#[test]
+fn parker_unpark_wakes_parked_thread() {
+    let p = Parker::default();
+    let u = p.unparker();
+    let unparked = AtomicU32::new(0);
+    thread::scope(|s| {
+        s.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            unparked.store(1, Ordering::Relaxed);
+            u.unpark();
+        });
+
+        p.park();
+        assert_eq!(unparked.load(Ordering::Relaxed), 1);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn parker_park_timeout() {
+    use std::time::Instant;
+
+    let p = Parker::new();
+    let t = Instant::now();
+    assert!(!p.park_timeout(Duration::from_millis(50)));
+    assert!(t.elapsed() >= Duration::from_millis(50));
+
+    p.unparker().unpark();
+    assert!(p.park_timeout(Duration::ZERO));
+    assert!(!p.park_deadline(Instant::now()));
+
+    let u = p.unparker();
+    thread::scope(|s| {
+        s.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            u.unpark();
+        });
+
+        assert!(p.park_deadline(Instant::now() + Duration::from_secs(10)));
+    });
+}
+
+#[test]
+fn stress_parker_many_unparkers() {
+    let threads = 64;
+    let p = Parker::new();
+    let u = p.unparker();
+    let sent = AtomicU32::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            let sent = &sent;
+            s.spawn(move || {
+                for _ in 0..100 {
+                    sent.fetch_add(1, Ordering::Release);
+                    u.unpark();
+                }
+            });
+        }
+
+        // Every park is released by an unpark made after the count was read.
+        while sent.load(Ordering::Acquire) < threads * 100 {
+            p.park();
+        }
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn owned_parker_unparked_from_spawned_thread() {
+    use wait_on_address::sync::OwnedParker;
+
+    let p = OwnedParker::new();
+    let u = p.unparker();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        u.unpark();
+    });
+
+    assert!(p.park_timeout(Duration::from_secs(10)));
+    handle.join().unwrap();
+
+    // The handle outlives the parker.
+    let u = p.unparker();
+    drop(p);
+    thread::spawn(move || u.unpark()).join().unwrap();
+}
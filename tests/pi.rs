@@ -0,0 +1,147 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering::Relaxed},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use wait_on_address::pi::{OWNER_MASK, PiMutex, WAITERS, pi_lock, pi_trylock, pi_unlock};
+
+#[test]
+fn pi_mutex_excludes() {
+    let m = PiMutex::new(0u64);
+    std::thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                for _ in 0..1_000 {
+                    *m.lock() += 1;
+                }
+            });
+        }
+    });
+
+    assert_eq!(m.into_inner(), 8_000);
+}
+
+#[test]
+fn pi_word_protocol() {
+    let word = AtomicU32::new(0);
+    assert!(pi_lock(&word, None));
+    assert_ne!(word.load(Relaxed) & OWNER_MASK, 0);
+    assert!(!pi_trylock(&word));
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            assert!(!pi_trylock(&word));
+            assert!(pi_lock(&word, None));
+            pi_unlock(&word);
+        });
+
+        while word.load(Relaxed) & WAITERS == 0 {
+            sleep(Duration::from_millis(1));
+        }
+        pi_unlock(&word);
+    });
+
+    assert_eq!(word.load(Relaxed), 0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn pi_word_holds_thread_id() {
+    let word = AtomicU32::new(0);
+    assert!(pi_trylock(&word));
+    assert_eq!(word.load(Relaxed), unsafe { libc::gettid() } as u32);
+    pi_unlock(&word);
+    assert_eq!(word.load(Relaxed), 0);
+}
+
+#[test]
+fn pi_mutex_timeout() {
+    let m = PiMutex::new(());
+    std::thread::scope(|s| {
+        let guard = m.lock();
+        s.spawn(|| {
+            assert!(m.try_lock().is_none());
+            let t = Instant::now();
+            assert!(m.try_lock_for(Duration::from_millis(50)).is_none());
+            assert!(t.elapsed() >= Duration::from_millis(40));
+        })
+        .join()
+        .unwrap();
+        drop(guard);
+
+        s.spawn(|| assert!(m.try_lock_for(Duration::from_millis(50)).is_some()));
+    });
+}
+
+/// Sets the real-time `SCHED_FIFO` policy for the calling thread.
+#[cfg(target_os = "linux")]
+fn set_fifo_priority(priority: i32) -> bool {
+    let param = libc::sched_param {
+        sched_priority: priority,
+    };
+    unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) == 0 }
+}
+
+/// Reads the effective kernel priority of a thread in this process.
+#[cfg(target_os = "linux")]
+fn kernel_priority(tid: i32) -> i64 {
+    let stat = std::fs::read_to_string(format!("/proc/self/task/{tid}/stat")).unwrap();
+    let fields = &stat[stat.rfind(')').unwrap() + 2..];
+    fields.split(' ').nth(15).unwrap().parse().unwrap()
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn pi_mutex_boosts_owner() {
+    use std::sync::{
+        atomic::{AtomicBool, AtomicI32},
+        mpsc::channel,
+    };
+
+    let m = PiMutex::new(());
+    let low_tid = AtomicI32::new(0);
+    let release = AtomicBool::new(false);
+    let (locked_tx, locked_rx) = channel();
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            if !set_fifo_priority(10) {
+                locked_tx.send(false).unwrap();
+                return;
+            }
+
+            let guard = m.lock();
+            low_tid.store(unsafe { libc::gettid() }, Relaxed);
+            locked_tx.send(true).unwrap();
+            while !release.load(Relaxed) {
+                sleep(Duration::from_millis(1));
+            }
+            drop(guard);
+        });
+
+        if !locked_rx.recv().unwrap() {
+            eprintln!("skipping priority inheritance test: SCHED_FIFO is not permitted");
+            return;
+        }
+
+        // A SCHED_FIFO priority of p is reported as -1 - p.
+        let tid = low_tid.load(Relaxed);
+        assert_eq!(kernel_priority(tid), -11);
+
+        s.spawn(|| {
+            assert!(set_fifo_priority(20));
+            drop(m.lock());
+        });
+
+        let t = Instant::now();
+        while kernel_priority(tid) != -21 {
+            assert!(
+                t.elapsed() < Duration::from_secs(5),
+                "owner was not boosted"
+            );
+            sleep(Duration::from_millis(1));
+        }
+
+        release.store(true, Relaxed);
+    });
+}
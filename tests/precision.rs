@@ -0,0 +1,67 @@
+#![cfg(feature = "std")]
+
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, Ordering::Relaxed},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use wait_on_address::{AtomicWait, Precision};
+
+#[test]
+fn high_precision_timeout() {
+    let a = AtomicU32::new(0);
+    let timeout = Duration::from_micros(1_500);
+
+    let mut overshoots: Vec<_> = (0..20)
+        .map(|_| {
+            let t = Instant::now();
+            a.wait_timeout_with_precision(0, timeout, Precision::High);
+            let elapsed = t.elapsed();
+            assert!(elapsed >= timeout);
+            elapsed - timeout
+        })
+        .collect();
+
+    overshoots.sort();
+    assert!(overshoots[overshoots.len() / 2] < Duration::from_micros(200));
+}
+
+#[test]
+fn high_precision_wait_woken_by_notify() {
+    let a = AtomicU64::new(0);
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            sleep(Duration::from_millis(20));
+            a.store(1, Relaxed);
+            a.notify_all();
+        });
+
+        let t = Instant::now();
+        while a.load(Relaxed) == 0 {
+            a.wait_timeout_with_precision(0, Duration::from_secs(10), Precision::High);
+        }
+        assert!(t.elapsed() < Duration::from_secs(5));
+    });
+}
+
+#[test]
+fn high_precision_wait_returns_on_change() {
+    let a = AtomicU32::new(1);
+    let t = Instant::now();
+    a.wait_timeout_with_precision(0, Duration::from_secs(10), Precision::High);
+    assert!(t.elapsed() < Duration::from_secs(5));
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn set_timer_slack() {
+    use wait_on_address::backend::set_timer_slack;
+
+    assert!(set_timer_slack(Duration::from_nanos(1)));
+    let a = AtomicU32::new(0);
+    let t = Instant::now();
+    a.wait_timeout_with_precision(0, Duration::from_millis(1), Precision::High);
+    assert!(t.elapsed() >= Duration::from_millis(1));
+    assert!(set_timer_slack(Duration::ZERO));
+}
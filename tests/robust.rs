@@ -0,0 +1,155 @@
+#![cfg(all(any(target_os = "linux", target_os = "android"), feature = "std"))]
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use wait_on_address::robust::{RobustMutex, take_over_robust_list};
+
+/// Places a new mutex in an anonymous mapping that is shared with child processes.
+fn shared_mutex<T>(value: T) -> &'static RobustMutex<T> {
+    unsafe {
+        let ptr = libc::mmap(
+            std::ptr::null_mut(),
+            size_of::<RobustMutex<T>>(),
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        assert_ne!(ptr, libc::MAP_FAILED);
+        let ptr = ptr as *mut RobustMutex<T>;
+        ptr.write(RobustMutex::new(value));
+        &*ptr
+    }
+}
+
+/// Waits for `child` to exit, and checks that it exited successfully.
+fn join_child(child: libc::pid_t) {
+    let t = Instant::now();
+    let mut status = 0;
+    while unsafe { libc::waitpid(child, &mut status, libc::WNOHANG) } == 0 {
+        if t.elapsed() > Duration::from_secs(5) {
+            unsafe { libc::kill(child, libc::SIGKILL) };
+            panic!("child process did not exit");
+        }
+        sleep(Duration::from_millis(1));
+    }
+    assert!(libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0);
+}
+
+#[cfg(target_env = "gnu")]
+#[test]
+fn robust_mutex_keeps_foreign_list() {
+    // Threads created by `glibc` already have a robust list, which
+    // must not be replaced without opting in.
+    let m = RobustMutex::new(());
+    let result = std::thread::spawn(move || drop(m.lock())).join();
+    assert!(result.is_err());
+}
+
+#[test]
+fn robust_mutex_excludes() {
+    let m = RobustMutex::new(0u64);
+    std::thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                take_over_robust_list();
+                for _ in 0..1_000 {
+                    *m.lock().unwrap() += 1;
+                }
+            });
+        }
+    });
+
+    assert_eq!(m.into_inner(), 8_000);
+}
+
+#[test]
+fn robust_mutex_try_lock() {
+    take_over_robust_list();
+    let m = RobustMutex::new(());
+    let a = RobustMutex::new(());
+    let guard = m.try_lock().unwrap().unwrap();
+    let other = a.lock().unwrap();
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            take_over_robust_list();
+            assert!(m.try_lock().is_none());
+        });
+    });
+
+    // Unlocking out of order must keep the robust list intact.
+    drop(guard);
+    assert!(m.try_lock().unwrap().is_ok());
+    drop(other);
+}
+
+#[test]
+fn robust_mutex_owner_thread_died() {
+    take_over_robust_list();
+    let m = RobustMutex::new(0);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            take_over_robust_list();
+            std::mem::forget(m.lock().unwrap());
+        });
+    });
+
+    let mut guard = m.lock().unwrap_err().into_inner();
+    assert_eq!(*guard, 0);
+    *guard = 1;
+    drop(guard);
+    assert_eq!(*m.lock().unwrap(), 1);
+}
+
+#[test]
+fn robust_mutex_owner_process_died() {
+    take_over_robust_list();
+    let m = shared_mutex(0);
+
+    match unsafe { libc::fork() } {
+        -1 => panic!("fork failed"),
+        0 => {
+            std::mem::forget(m.lock().unwrap());
+            unsafe { libc::_exit(0) };
+        }
+        child => join_child(child),
+    }
+
+    let mut guard = m.lock().unwrap_err().into_inner();
+    *guard += 1;
+    drop(guard);
+    assert_eq!(*m.lock().unwrap(), 1);
+}
+
+#[test]
+fn robust_mutex_wakes_waiter_when_owner_dies() {
+    take_over_robust_list();
+    let m = shared_mutex(0);
+    let locked = shared_mutex(AtomicBool::new(false));
+
+    match unsafe { libc::fork() } {
+        -1 => panic!("fork failed"),
+        0 => {
+            let mut guard = m.lock().unwrap();
+            *guard = 1;
+            locked.lock().unwrap().store(true, Relaxed);
+            sleep(Duration::from_millis(50));
+            std::mem::forget(guard);
+            unsafe { libc::_exit(0) };
+        }
+        child => {
+            while !locked.lock().unwrap().load(Relaxed) {
+                sleep(Duration::from_millis(1));
+            }
+
+            let guard = m.lock().unwrap_err().into_inner();
+            assert_eq!(*guard, 1);
+            drop(guard);
+            join_child(child);
+        }
+    }
+}
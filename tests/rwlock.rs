@@ -0,0 +1,345 @@
+use std::{
+    sync::Barrier,
+    thread,
+    time::{Duration, Instant},
+};
+use wait_on_address::sync::{RwLock, RwLockPolicy, RwLockUpgradableReadGuard, RwLockWriteGuard};
+
+const POLICIES: [RwLockPolicy; 3] = [
+    RwLockPolicy::WriterPreferring,
+    RwLockPolicy::ReaderPreferring,
+    RwLockPolicy::PhaseFair,
+];
+
+/// Waits until `condition` holds, failing the test if it takes too long.
+fn wait_until(condition: impl Fn() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn rwlock_read_write() {
+    let l = RwLock::new(1);
+    assert_eq!(l.policy(), RwLockPolicy::WriterPreferring);
+    *l.write() += 1;
+    assert_eq!(*l.read(), 2);
+    assert_eq!(l.into_inner(), 2);
+}
+
+#[test]
+fn rwlock_readers_share() {
+    for policy in POLICIES {
+        let l = RwLock::with_policy((), policy);
+        let barrier = Barrier::new(4);
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    let _guard = l.read();
+                    barrier.wait();
+                    assert!(l.try_write().is_none());
+                    barrier.wait();
+                });
+            }
+        });
+
+        assert!(l.try_write().is_some());
+    }
+}
+
+#[test]
+fn rwlock_writer_excludes() {
+    for policy in POLICIES {
+        let l = RwLock::with_policy(0, policy);
+        let guard = l.write();
+        thread::scope(|s| {
+            s.spawn(|| {
+                assert!(l.try_read().is_none());
+                assert!(l.try_write().is_none());
+                assert!(l.try_upgradable_read().is_none());
+            });
+        });
+
+        thread::scope(|s| {
+            s.spawn(|| assert_eq!(*l.read(), 1));
+            thread::sleep(Duration::from_millis(20));
+            let mut guard = guard;
+            *guard = 1;
+        });
+    }
+}
+
+#[test]
+fn rwlock_writer_preferring_blocks_new_readers() {
+    let l = RwLock::with_policy(0, RwLockPolicy::WriterPreferring);
+    let guard = l.read();
+    thread::scope(|s| {
+        s.spawn(|| *l.write() = 1);
+        wait_until(|| l.try_read().is_none());
+        drop(guard);
+    });
+
+    assert_eq!(*l.read(), 1);
+}
+
+#[test]
+fn rwlock_reader_preferring_admits_new_readers() {
+    let l = RwLock::with_policy(0, RwLockPolicy::ReaderPreferring);
+    let guard = l.read();
+    thread::scope(|s| {
+        s.spawn(|| *l.write() = 1);
+        thread::sleep(Duration::from_millis(50));
+        assert!(l.try_read().is_some());
+        drop(guard);
+    });
+
+    assert_eq!(*l.read(), 1);
+}
+
+#[test]
+fn rwlock_phase_fair_alternates() {
+    let l = RwLock::with_policy(Vec::new(), RwLockPolicy::PhaseFair);
+    let guard = l.write();
+    thread::scope(|s| {
+        s.spawn(|| {
+            let data = l.read();
+            assert_eq!(*data, ["first writer"]);
+        });
+
+        // The reader arrives before the second writer, so it
+        // acquires the lock as soon as the first writer is done.
+        thread::sleep(Duration::from_millis(50));
+        s.spawn(|| l.write().push("second writer"));
+        thread::sleep(Duration::from_millis(50));
+
+        let mut guard = guard;
+        guard.push("first writer");
+    });
+
+    assert_eq!(*l.read(), ["first writer", "second writer"]);
+}
+
+#[test]
+fn rwlock_upgradable() {
+    for policy in POLICIES {
+        let l = RwLock::with_policy(0, policy);
+        let upgradable = l.upgradable_read();
+        assert!(l.try_upgradable_read().is_none());
+        assert!(l.try_write().is_none());
+
+        let barrier = Barrier::new(2);
+        thread::scope(|s| {
+            s.spawn(|| {
+                let _reader = l.try_read().unwrap();
+                barrier.wait();
+                thread::sleep(Duration::from_millis(20));
+            });
+
+            barrier.wait();
+            let upgradable = RwLockUpgradableReadGuard::try_upgrade(upgradable).unwrap_err();
+            let mut writer = RwLockUpgradableReadGuard::upgrade(upgradable);
+            *writer = 1;
+        });
+
+        let upgradable = l.upgradable_read();
+        let reader = RwLockUpgradableReadGuard::downgrade(upgradable);
+        assert!(l.try_upgradable_read().is_some());
+        drop(reader);
+        assert_eq!(*l.read(), 1);
+    }
+}
+
+#[test]
+fn rwlock_downgrade() {
+    for policy in POLICIES {
+        let l = RwLock::with_policy(0, policy);
+        let mut writer = l.write();
+        *writer = 1;
+        let reader = RwLockWriteGuard::downgrade(writer);
+        assert_eq!(*l.try_read().unwrap(), 1);
+        assert!(l.try_write().is_none());
+        drop(reader);
+
+        let writer = l.write();
+        let upgradable = RwLockWriteGuard::downgrade_to_upgradable(writer);
+        assert!(l.try_read().is_some());
+        assert!(l.try_upgradable_read().is_none());
+        let writer = RwLockUpgradableReadGuard::try_upgrade(upgradable).unwrap();
+        drop(writer);
+        assert!(l.try_write().is_some());
+    }
+}
+
+#[test]
+fn rwlock_downgrade_wakes_readers() {
+    let l = RwLock::new(0);
+    let writer = l.write();
+    thread::scope(|s| {
+        s.spawn(|| assert_eq!(*l.read(), 1));
+        thread::sleep(Duration::from_millis(20));
+
+        let mut writer = writer;
+        *writer = 1;
+        let _reader = RwLockWriteGuard::downgrade(writer);
+        thread::sleep(Duration::from_millis(20));
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn rwlock_timed() {
+    for policy in POLICIES {
+        let l = RwLock::with_policy(0, policy);
+        let reader = l.read();
+        thread::scope(|s| {
+            s.spawn(|| {
+                let t = Instant::now();
+                assert!(l.try_write_for(Duration::from_millis(50)).is_none());
+                assert!(t.elapsed() >= Duration::from_millis(50));
+            });
+        });
+
+        // The timed out writer must not keep readers out.
+        assert!(l.try_read_for(Duration::from_secs(10)).is_some());
+        drop(reader);
+
+        let writer = l.write();
+        thread::scope(|s| {
+            s.spawn(|| {
+                let deadline = Instant::now() + Duration::from_millis(50);
+                assert!(l.try_read_until(deadline).is_none());
+                assert!(Instant::now() >= deadline);
+                assert!(
+                    l.try_upgradable_read_for(Duration::from_millis(10))
+                        .is_none()
+                );
+            });
+        });
+
+        thread::scope(|s| {
+            s.spawn(|| assert!(l.try_read_for(Duration::from_secs(10)).is_some()));
+            s.spawn(|| assert!(l.try_write_for(Duration::from_secs(10)).is_some()));
+            s.spawn(|| assert!(l.try_upgradable_read_for(Duration::from_secs(10)).is_some()));
+            thread::sleep(Duration::from_millis(20));
+            drop(writer);
+        });
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn rwlock_try_upgrade_for() {
+    for policy in POLICIES {
+        let l = RwLock::with_policy(0, policy);
+        let barrier = Barrier::new(2);
+        thread::scope(|s| {
+            s.spawn(|| {
+                let _reader = l.read();
+                barrier.wait();
+                barrier.wait();
+                thread::sleep(Duration::from_millis(20));
+            });
+
+            barrier.wait();
+            let upgradable = l.upgradable_read();
+            let upgradable =
+                RwLockUpgradableReadGuard::try_upgrade_for(upgradable, Duration::from_millis(50))
+                    .unwrap_err();
+
+            // Readers held back by the upgrade may proceed once it gives up.
+            assert!(l.try_read_for(Duration::from_secs(10)).is_some());
+            barrier.wait();
+
+            let deadline = Instant::now() + Duration::from_secs(10);
+            let mut writer =
+                RwLockUpgradableReadGuard::try_upgrade_until(upgradable, deadline).unwrap();
+            *writer = 1;
+        });
+
+        assert_eq!(*l.read(), 1);
+    }
+}
+
+#[test]
+fn stress_rwlock_many_threads() {
+    for policy in POLICIES {
+        // The two halves are only ever updated together, so readers must never see them differ.
+        let l = RwLock::with_policy((0u64, 0u64), policy);
+        let threads = 32;
+        thread::scope(|s| {
+            for i in 0..threads {
+                let l = &l;
+                s.spawn(move || {
+                    for j in 0..500 {
+                        match (i + j) % 4 {
+                            0 => {
+                                let mut guard = l.write();
+                                guard.0 += 1;
+                                guard.1 += 1;
+                            }
+                            1 => {
+                                let guard = l.upgradable_read();
+                                assert_eq!(guard.0, guard.1);
+                                let mut guard = RwLockUpgradableReadGuard::upgrade(guard);
+                                guard.0 += 1;
+                                guard.1 += 1;
+                            }
+                            _ => {
+                                let guard = l.read();
+                                assert_eq!(guard.0, guard.1);
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(l.into_inner(), (threads * 250, threads * 250));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stress_rwlock_timed_and_blocking() {
+    for policy in POLICIES {
+        let l = RwLock::with_policy(0u64, policy);
+        let threads = 16;
+        thread::scope(|s| {
+            for i in 0..threads {
+                let l = &l;
+                s.spawn(move || {
+                    let mut done = 0;
+                    while done < 500 {
+                        match i % 4 {
+                            0 => {
+                                *l.write() += 1;
+                                done += 1;
+                            }
+                            1 => {
+                                if let Some(mut guard) = l.try_write_for(Duration::from_micros(10))
+                                {
+                                    *guard += 1;
+                                    done += 1;
+                                }
+                            }
+                            2 => {
+                                let _guard = l.read();
+                            }
+                            _ => {
+                                let _guard = l.try_read_for(Duration::from_micros(10));
+                            }
+                        }
+
+                        if i % 4 >= 2 {
+                            done += 1;
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(l.into_inner(), threads / 2 * 500);
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::Duration,
+};
+use wait_on_address::sync::Semaphore;
+
+#[test]
+fn semaphore_acquire_release() {
+    let s = Semaphore::new(3);
+    let a = s.acquire(2);
+    assert_eq!(a.num_permits(), 2);
+    assert_eq!(s.available_permits(), 1);
+    assert!(s.try_acquire(2).is_none());
+    let b = s.try_acquire(1).unwrap();
+    assert_eq!(s.available_permits(), 0);
+    drop(a);
+    drop(b);
+    assert_eq!(s.available_permits(), 3);
+}
+
+#[test]
+fn semaphore_add_permits_and_forget() {
+    let s = Semaphore::new(0);
+    assert!(s.try_acquire(1).is_none());
+    s.add_permits(2);
+    s.acquire(2).forget();
+    assert_eq!(s.available_permits(), 0);
+
+    // Acquiring no permits always succeeds.
+    assert_eq!(s.acquire(0).num_permits(), 0);
+}
+
+#[test]
+fn semaphore_weighted_blocks() {
+    let s = Semaphore::new(1);
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let permit = s.acquire(3);
+            assert_eq!(permit.num_permits(), 3);
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(s.available_permits(), 1);
+        s.add_permits(1);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(s.available_permits(), 2);
+        s.add_permits(1);
+    });
+
+    assert_eq!(s.available_permits(), 3);
+}
+
+#[test]
+fn semaphore_large_waiter_does_not_strand_small_ones() {
+    let s = Semaphore::new(0);
+    thread::scope(|scope| {
+        scope.spawn(|| s.acquire(10).forget());
+        thread::sleep(Duration::from_millis(20));
+        for _ in 0..4 {
+            scope.spawn(|| s.acquire(1).forget());
+        }
+
+        thread::sleep(Duration::from_millis(20));
+        for _ in 0..4 {
+            s.add_permits(1);
+        }
+
+        while s.available_permits() != 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        s.add_permits(10);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn semaphore_acquire_timeout() {
+    use std::time::Instant;
+
+    let s = Semaphore::new(1);
+    let t = Instant::now();
+    assert!(s.acquire_timeout(2, Duration::from_millis(50)).is_none());
+    assert!(t.elapsed() >= Duration::from_millis(50));
+    assert_eq!(s.available_permits(), 1);
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            assert!(s.acquire_until(2, deadline).is_some());
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        s.add_permits(1);
+    });
+}
+
+#[test]
+fn stress_semaphore_bounds_concurrency() {
+    let s = Semaphore::new(4);
+    let active = AtomicU32::new(0);
+    let max = AtomicU32::new(0);
+    thread::scope(|scope| {
+        for i in 0..32 {
+            let (s, active, max) = (&s, &active, &max);
+            scope.spawn(move || {
+                for _ in 0..200 {
+                    let n = i % 3 + 1;
+                    let _permit = s.acquire(n);
+                    let now = active.fetch_add(n, Ordering::SeqCst) + n;
+                    max.fetch_max(now, Ordering::SeqCst);
+                    active.fetch_sub(n, Ordering::SeqCst);
+                }
+            });
+        }
+    });
+
+    assert!(max.into_inner() <= 4);
+    assert_eq!(s.available_permits(), 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stress_semaphore_timed_and_blocking() {
+    let s = Semaphore::new(2);
+    thread::scope(|scope| {
+        for i in 0..16 {
+            let s = &s;
+            scope.spawn(move || {
+                let mut done = 0;
+                while done < 200 {
+                    let n = i % 2 + 1;
+                    let permit = if i % 4 < 2 {
+                        Some(s.acquire(n))
+                    } else {
+                        s.acquire_timeout(n, Duration::from_micros(10))
+                    };
+
+                    if permit.is_some() {
+                        done += 1;
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(s.available_permits(), 2);
+}
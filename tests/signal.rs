@@ -0,0 +1,74 @@
+#![cfg(unix)]
+
+use std::{
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering::Relaxed},
+    time::Duration,
+};
+use wait_on_address::{
+    AtomicWait,
+    backend::{Backend, backend},
+};
+
+static FLAG32: AtomicU32 = AtomicU32::new(0);
+static FLAG64: AtomicU64 = AtomicU64::new(0);
+static NOTIFIED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn handle_sigusr1(_: libc::c_int) {
+    FLAG32.store(1, Relaxed);
+    FLAG64.store(1, Relaxed);
+    let notified32 = FLAG32.notify_one_signal_safe();
+    let notified64 = FLAG64.notify_all_signal_safe();
+    NOTIFIED.store(notified32 && notified64, Relaxed);
+}
+
+#[test]
+fn notify_from_signal_handler() {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handle_sigusr1 as *const () as libc::sighandler_t;
+        assert_eq!(
+            libc::sigaction(libc::SIGUSR1, &action, std::ptr::null_mut()),
+            0
+        );
+    }
+
+    // Without a signal-safe backend, the handler cannot notify, so the
+    // watchdogs poll instead of relying on the wakeup.
+    let native = cfg!(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "watchos"
+    ));
+    let expected = match backend() {
+        Backend::Platform => native || !cfg!(feature = "std"),
+        Backend::Fallback => !cfg!(feature = "std"),
+        Backend::Spin => true,
+        Backend::Custom(_) => false,
+    };
+    let timeout = if expected {
+        Duration::from_secs(60)
+    } else {
+        Duration::from_millis(10)
+    };
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            while FLAG32.load(Relaxed) == 0 {
+                FLAG32.wait_timeout(0, timeout);
+            }
+        });
+        s.spawn(|| {
+            while FLAG64.load(Relaxed) == 0 {
+                FLAG64.wait_timeout(0, timeout);
+            }
+        });
+
+        std::thread::sleep(Duration::from_millis(20));
+        unsafe { libc::raise(libc::SIGUSR1) };
+    });
+
+    assert_eq!(NOTIFIED.load(Relaxed), expected);
+}
@@ -0,0 +1,59 @@
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering::Relaxed},
+    thread::sleep,
+    time::Duration,
+};
+use wait_on_address::{
+    AtomicWait,
+    backend::{Backend, set_backend, set_idle_hook, set_wake_hook},
+};
+
+static IDLES: AtomicUsize = AtomicUsize::new(0);
+static WAKES: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn spin_calls_hooks() {
+    if cfg!(feature = "force-fallback") {
+        assert_eq!(set_backend(Backend::Spin), Err(Backend::Fallback));
+        return;
+    }
+
+    assert_eq!(set_backend(Backend::Spin), Ok(()));
+    set_idle_hook(Some(|| {
+        IDLES.fetch_add(1, Relaxed);
+        sleep(Duration::from_millis(1));
+    }));
+    set_wake_hook(Some(|| {
+        WAKES.fetch_add(1, Relaxed);
+    }));
+
+    // A wait on a different value returns without idling.
+    let a = AtomicU32::new(0);
+    a.wait(1);
+    assert_eq!(IDLES.load(Relaxed), 0);
+
+    // A wait on the current value idles once, and then returns spuriously.
+    a.wait_timeout(0, Duration::from_secs(60));
+    assert_eq!(IDLES.load(Relaxed), 1);
+
+    let b = AtomicU64::new(0);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            sleep(Duration::from_millis(20));
+            b.store(1, Relaxed);
+            b.notify_all();
+        });
+        while b.load(Relaxed) == 0 {
+            b.wait(0);
+        }
+    });
+
+    assert!(IDLES.load(Relaxed) > 1);
+    assert_eq!(WAKES.load(Relaxed), 1);
+
+    set_idle_hook(None);
+    set_wake_hook(None);
+    a.wait(0);
+    a.notify_one();
+    assert_eq!(WAKES.load(Relaxed), 1);
+}
@@ -72,6 +72,34 @@ fn stress_many_waiters_notify_all() {
     assert_eq!(woke.load(Relaxed), threads);
 }
 
+#[test]
+fn stress_many_waiters_notify_all_u64() {
+    use std::{sync::Arc, sync::atomic::AtomicU64};
+    let a = Arc::new(AtomicU64::new(0));
+    let woke = Arc::new(AtomicU32::new(0));
+
+    let threads = 64;
+    std::thread::scope(|s| {
+        for _ in 0..threads {
+            let a = a.clone();
+            let woke = woke.clone();
+            s.spawn(move || {
+                while a.load(Relaxed) == 0 {
+                    a.wait(0);
+                }
+                woke.fetch_add(1, Relaxed);
+            });
+        }
+
+        // Give threads time to start waiting
+        sleep(Duration::from_millis(50));
+        a.store(1 << 32, Relaxed);
+        a.notify_all();
+    });
+
+    assert_eq!(woke.load(Relaxed), threads);
+}
+
 #[test]
 fn stress_ping_pong_many_iters() {
     use std::sync::Arc;
@@ -0,0 +1,41 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use common::CountingBackend;
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, Ordering::Relaxed},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use wait_on_address::{AtomicWait, backend::set_thread_backend};
+
+static YIELDING: CountingBackend = CountingBackend::new();
+
+#[test]
+fn thread_backend_is_per_thread() {
+    let a = AtomicU32::new(0);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            assert!(set_thread_backend(Some(&YIELDING)).is_none());
+            while a.load(Relaxed) == 0 {
+                a.wait(0);
+            }
+            a.notify_one();
+            assert!(set_thread_backend(None).is_some());
+        });
+
+        // This thread keeps the global backend, so it really sleeps.
+        let t = Instant::now();
+        let b = AtomicU64::new(0);
+        b.wait_timeout(0, Duration::from_millis(50));
+        assert!(t.elapsed() >= Duration::from_millis(40));
+
+        sleep(Duration::from_millis(10));
+        a.store(1, Relaxed);
+        a.notify_one();
+    });
+
+    assert!(YIELDING.waits.load(Relaxed) > 0);
+    assert_eq!(YIELDING.notifies.load(Relaxed), 1);
+}
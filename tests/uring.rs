@@ -0,0 +1,104 @@
+#![cfg(all(any(target_os = "linux", target_os = "android"), feature = "io-uring"))]
+
+use io_uring::{IoUring, squeue::Entry};
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, Ordering::Relaxed},
+    thread::sleep,
+    time::Duration,
+};
+use wait_on_address::{
+    AtomicWait,
+    backend::{Backend, backend},
+    uring,
+};
+
+/// Creates a ring, or returns `None` if `io_uring` futex operations are unavailable.
+fn ring() -> Option<IoUring> {
+    if backend() != Backend::Platform {
+        return None;
+    }
+
+    let mut ring = IoUring::new(8).ok()?;
+    let a = AtomicU32::new(0);
+    (submit(&mut ring, &uring::notify_one32(&a)) == 0).then_some(ring)
+}
+
+/// Submits `entry`, and returns the result of its completion.
+fn submit(ring: &mut IoUring, entry: &Entry) -> i32 {
+    unsafe { ring.submission().push(entry).unwrap() };
+    ring.submit_and_wait(1).unwrap();
+    ring.completion().next().unwrap().result()
+}
+
+#[test]
+fn uring_wait_woken_by_notify() {
+    let Some(mut ring) = ring() else { return };
+    let a = AtomicU32::new(0);
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            sleep(Duration::from_millis(20));
+            a.store(1, Relaxed);
+            a.notify_one();
+        });
+
+        assert_eq!(submit(&mut ring, &uring::wait32(&a, 0)), 0);
+    });
+
+    assert_eq!(submit(&mut ring, &uring::wait32(&a, 0)), -libc::EAGAIN);
+}
+
+#[test]
+fn uring_notify_wakes_wait() {
+    let Some(mut ring) = ring() else { return };
+    let a = AtomicU32::new(0);
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            while a.load(Relaxed) == 0 {
+                a.wait(0);
+            }
+        });
+
+        sleep(Duration::from_millis(20));
+        a.store(1, Relaxed);
+        assert!(submit(&mut ring, &uring::notify_all32(&a)) >= 0);
+    });
+}
+
+#[test]
+fn uring_wait64_woken_by_notify() {
+    let Some(mut ring) = ring() else { return };
+    let a = AtomicU64::new(u64::MAX);
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            sleep(Duration::from_millis(20));
+            a.store(0, Relaxed);
+            a.notify_all();
+        });
+
+        let entry = uring::wait64(&a, u64::MAX).unwrap();
+        assert_eq!(submit(&mut ring, &entry), 0);
+    });
+
+    assert!(uring::wait64(&a, u64::MAX).is_none());
+}
+
+#[test]
+fn uring_waitv_reports_index() {
+    let Some(mut ring) = ring() else { return };
+    let a = AtomicU32::new(0);
+    let b = AtomicU32::new(0);
+    let waiters = [uring::waiter32(&a, 0), uring::waiter32(&b, 0)];
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            sleep(Duration::from_millis(20));
+            b.store(1, Relaxed);
+            b.notify_all();
+        });
+
+        assert_eq!(submit(&mut ring, &uring::waitv(&waiters)), 1);
+    });
+}